@@ -0,0 +1,304 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    cli::OutputFormat,
+    config::Config,
+    systemd::{BackendKind, OperationType, Scope, Signal, Target, UnitType},
+    tui::{FilterQuery, QuickFilter},
+};
+
+pub const USAGE: &str = "Usage: sm [OPTIONS] [COMMAND]
+
+Without a command the interactive interface is opened.
+
+Commands:
+  list [FILTER]...     Print the units matching FILTER like the filter of the interface,
+                       or all of them
+  pick                 Open the interface to choose units and print their names
+  status <UNIT>...     Print the status of the units
+  start <UNIT>...      Start the units, likewise stop, reload, restart, enable, disable,
+                       enable-now, disable-now, reload-or-restart, try-restart, mask,
+                       unmask, kill and reset-failed
+  daemon-reload        Reload the configuration of the service manager
+
+Options:
+  --backend <BACKEND>  How units are listed and controlled: systemctl (default), fake,
+                       dbus and dbus-session when built with the `dbus` feature
+  --config <FILE>      Read the configuration from FILE instead of
+                       $XDG_CONFIG_HOME/systemctl-manager/config.toml
+  --user               Manage the units of the user service manager
+  --system             Manage the units of the system service manager
+  --type <TYPE>        Kind of units to list: service (default), socket, target, timer,
+                       path, mount, automount, swap, slice, scope, device or all
+  --files              List the installed unit files instead of the loaded units
+  --loaded             List the loaded units
+  --failed             Show the failed units only, in the interface or with list
+  --output <FORMAT>    How list prints the units: plain (default), json or tsv
+  --refresh <SECONDS>  How often the unit list is refreshed, 0 turns it off
+  --signal <SIGNAL>    Signal kill sends: TERM (default), KILL, HUP, INT, USR1 or USR2
+  -h, --help           Print this help
+
+Commands exit with 0 on success, 1 when listing the units or any operation failed or picking
+was cancelled and 2 on invalid arguments.";
+
+/// What to do instead of opening the interface.
+pub enum Subcommand {
+    List {
+        filter: FilterQuery,
+        output: OutputFormat,
+    },
+    Pick,
+    Operation {
+        operation_type: OperationType,
+        units: Vec<String>,
+    },
+}
+
+/// Command line options. Those left out fall back to the configuration.
+#[derive(Default)]
+pub struct Args {
+    pub backend: BackendKind,
+    pub config: Option<PathBuf>,
+    pub scope: Option<Scope>,
+    pub unit_type: Option<UnitType>,
+    pub target: Option<Target>,
+    pub refresh: Option<u64>,
+    pub quick_filter: Option<QuickFilter>,
+    pub subcommand: Option<Subcommand>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut positionals = Vec::new();
+        let mut output = None;
+        let mut signal = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--user" => parsed.scope = Some(Scope::User),
+                "--system" => parsed.scope = Some(Scope::System),
+                "--files" => parsed.target = Some(Target::Files),
+                "--loaded" => parsed.target = Some(Target::Memory),
+                "--failed" => parsed.quick_filter = Some(QuickFilter::Failed),
+                "--config" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --config"))?;
+                    parsed.config = Some(PathBuf::from(value));
+                }
+                "--backend" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --backend"))?;
+                    parsed.backend = value.parse()?;
+                }
+                "--type" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --type"))?;
+                    parsed.unit_type = Some(value.parse()?);
+                }
+                "--output" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --output"))?;
+                    output = Some(value.parse()?);
+                }
+                "--signal" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --signal"))?;
+                    signal = Some(value.parse()?);
+                }
+                "--refresh" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("Missing value for --refresh"))?;
+                    parsed.refresh = Some(value.parse().map_err(|_err| {
+                        format!("Invalid value '{value}' for --refresh, expected seconds")
+                    })?);
+                }
+                _ if arg.starts_with('-') => {
+                    return Err(format!("Unexpected argument '{arg}'"));
+                }
+                _ => positionals.push(arg),
+            }
+        }
+
+        parsed.subcommand = parsed.parse_subcommand(positionals, output, signal)?;
+
+        Ok(parsed)
+    }
+
+    fn parse_subcommand(
+        &self,
+        positionals: Vec<String>,
+        output: Option<OutputFormat>,
+        signal: Option<Signal>,
+    ) -> Result<Option<Subcommand>, String> {
+        let mut positionals = positionals.into_iter();
+        let name = positionals.next();
+
+        if name.as_deref() != Some("list") && output.is_some() {
+            return Err(String::from("--output only applies to the list command"));
+        }
+
+        // Both open the interface with the options, list only filters with `--failed`.
+        let opens_interface = matches!(name.as_deref(), None | Some("pick"));
+
+        if !opens_interface && name.as_deref() != Some("list") && self.quick_filter.is_some() {
+            return Err(String::from(
+                "--failed only applies to the interface and the list and pick commands",
+            ));
+        }
+
+        if !opens_interface && self.refresh.is_some() {
+            return Err(String::from(
+                "--refresh only applies to the interface and the pick command",
+            ));
+        }
+
+        let Some(name) = name else {
+            return Ok(None);
+        };
+
+        if name == "list" {
+            // Terms given as separate arguments all have to match, as next to each other.
+            let filter = positionals
+                .collect::<Vec<_>>()
+                .join(" ")
+                .parse()
+                .map_err(|err| format!("Invalid filter: {err}"))?;
+
+            return Ok(Some(Subcommand::List {
+                filter,
+                output: output.unwrap_or_default(),
+            }));
+        }
+
+        if name == "pick" {
+            if let Some(arg) = positionals.next() {
+                return Err(format!("Unexpected argument '{arg}'"));
+            }
+
+            return Ok(Some(Subcommand::Pick));
+        }
+
+        let mut operation_type = name
+            .parse()
+            .map_err(|_err| format!("Unknown command '{name}'"))?;
+        let units: Vec<String> = positionals.collect();
+
+        if let Some(signal) = signal {
+            let OperationType::Kill(_) = operation_type else {
+                return Err(String::from("--signal only applies to the kill command"));
+            };
+
+            operation_type = OperationType::Kill(signal);
+        }
+
+        if operation_type.is_global() {
+            if let Some(arg) = units.first() {
+                return Err(format!("Unexpected argument '{arg}'"));
+            }
+        } else if units.is_empty() {
+            return Err(format!("Missing unit for {name}"));
+        }
+
+        Ok(Some(Subcommand::Operation {
+            operation_type,
+            units,
+        }))
+    }
+
+    /// Overrides the settings of `config` given on the command line.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(scope) = self.scope {
+            config.listing.scope = scope;
+        }
+
+        if let Some(unit_type) = self.unit_type {
+            config.listing.unit_type = unit_type;
+        }
+
+        if let Some(target) = self.target {
+            config.listing.target = target;
+        }
+
+        if let Some(quick_filter) = self.quick_filter {
+            config.quick_filter = Some(quick_filter);
+        }
+
+        if let Some(refresh) = self.refresh {
+            config.refresh_interval = match refresh {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{systemd::Service, tui::FilterMode};
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn joins_the_filter_arguments_of_list() {
+        let Some(Subcommand::List { filter, .. }) = parse(&["list", "state:failed", "name:ng"])
+            .unwrap()
+            .subcommand
+        else {
+            panic!("not a list command");
+        };
+        let service = Service {
+            name: String::from("nginx.service"),
+            active_state: String::from("failed"),
+            ..Service::default()
+        };
+
+        assert!(filter.find(&service, FilterMode::Fuzzy).is_some());
+        assert!(
+            filter
+                .find(
+                    &Service {
+                        active_state: String::from("active"),
+                        ..service
+                    },
+                    FilterMode::Fuzzy
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_interface_options_with_operations() {
+        assert_eq!(
+            parse(&["--failed", "start", "nginx.service"])
+                .err()
+                .as_deref(),
+            Some("--failed only applies to the interface and the list and pick commands")
+        );
+        assert_eq!(
+            parse(&["list", "--refresh", "2"]).err().as_deref(),
+            Some("--refresh only applies to the interface and the pick command")
+        );
+        assert!(parse(&["list", "--failed"]).is_ok());
+        assert!(parse(&["pick", "--failed", "--refresh", "2"]).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        assert_eq!(
+            parse(&["list", "state:"]).err().as_deref(),
+            Some("Invalid filter: missing value after 'state:'")
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::{Write, stdout};
+
+mod output;
+
+use crate::{
+    args::Subcommand,
+    config::Config,
+    systemd::{Backend, Listing, Operation, OperationType, Target},
+    tui::{FilterMode, FilterQuery, QuickFilter, Tui},
+};
+
+pub use output::OutputFormat;
+
+/// Runs `subcommand` and returns the exit code.
+pub fn run(subcommand: &Subcommand, backend: Box<dyn Backend>, config: &Config) -> i32 {
+    let listing = config.listing;
+
+    match subcommand {
+        Subcommand::List { filter, output } => list(
+            backend.as_ref(),
+            listing,
+            filter,
+            config.filter_mode,
+            config.quick_filter,
+            *output,
+        ),
+        Subcommand::Pick => pick(backend, config),
+        Subcommand::Operation {
+            operation_type,
+            units,
+        } => execute(backend.as_ref(), listing, *operation_type, units),
+    }
+}
+
+/// Prints the units picked in the interface, one per line.
+fn pick(backend: Box<dyn Backend>, config: &Config) -> i32 {
+    // The interface is torn down when the `Tui` is dropped, before anything is printed.
+    let picked = match Tui::new(backend, config) {
+        Ok(mut tui) => tui.pick(),
+        Err(err) => {
+            eprintln!("Could not open the interface: {err}");
+            return 1;
+        }
+    };
+
+    let Some(names) = picked else {
+        return 1;
+    };
+
+    let mut stdout = stdout().lock();
+
+    for name in names {
+        if writeln!(stdout, "{name}").is_err() {
+            break;
+        }
+    }
+
+    0
+}
+
+/// Prints the units that match `filter` in `mode` and `quick_filter`, ranked like the
+/// interface does.
+fn list(
+    backend: &dyn Backend,
+    listing: Listing,
+    filter: &FilterQuery,
+    mode: FilterMode,
+    quick_filter: Option<QuickFilter>,
+    output: OutputFormat,
+) -> i32 {
+    let needs_details =
+        filter.needs_details() || quick_filter.is_some_and(QuickFilter::needs_details);
+    let loaded = listing.load(backend).and_then(|mut services| {
+        if listing.target == Target::Memory && needs_details {
+            backend.fill_details(listing.scope, &mut services)?;
+        }
+
+        Ok(services)
+    });
+    let services = match loaded {
+        Ok(services) => services,
+        Err(err) => {
+            eprintln!(
+                "Could not load {} units of the {} manager: {err}",
+                listing.unit_type, listing.scope
+            );
+            return 1;
+        }
+    };
+
+    let services: Vec<_> = filter
+        .rank(services, mode, |service| service)
+        .into_iter()
+        .map(|(_, service)| service)
+        .filter(|service| quick_filter.is_none_or(|quick_filter| quick_filter.matches(service)))
+        .collect();
+
+    let mut stdout = stdout().lock();
+
+    // Writing stops quietly when the reader went away, e.g. `sm list | head`.
+    for line in output.format(&services, listing.target) {
+        if writeln!(stdout, "{line}").is_err() {
+            break;
+        }
+    }
+
+    0
+}
+
+/// Executes the operation on every unit, even when it fails on one of them.
+#[allow(clippy::print_stdout)]
+fn execute(
+    backend: &dyn Backend,
+    listing: Listing,
+    operation_type: OperationType,
+    units: &[String],
+) -> i32 {
+    let mut code = 0;
+
+    for operation in Operation::for_units(operation_type, units.to_vec(), listing.scope) {
+        match backend.execute(&operation) {
+            Ok(output) => {
+                if operation_type == OperationType::Status {
+                    print!("{}", output.stdout);
+                }
+
+                if !output.success {
+                    code = 1;
+                }
+
+                if !output.success || operation_type != OperationType::Status {
+                    eprintln!("{}", output.summary(&operation));
+                }
+            }
+            Err(err) => {
+                eprintln!("{operation} could not be executed: {err}");
+                code = 1;
+            }
+        }
+    }
+
+    code
+}
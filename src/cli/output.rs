@@ -0,0 +1,182 @@
+use std::{fmt::Write, str::FromStr};
+
+use crate::systemd::{Listing, Service, Target};
+
+/// How `sm list` prints the units.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Aligned columns, like the interface shows them.
+    #[default]
+    Plain,
+    /// One object per unit with every field, empty fields are null.
+    Json,
+    /// A header line and the listed columns separated by tabs.
+    Tsv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(format!(
+                "Unknown output format '{value}', expected one of: plain, json, tsv"
+            )),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    if value.is_empty() {
+        return String::from("null");
+    }
+
+    let mut result = String::from("\"");
+
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            _ if ch.is_control() => {
+                let _ = write!(result, "\\u{:04x}", u32::from(ch));
+            }
+            _ => result.push(ch),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn json_object(service: &Service) -> String {
+    let fields = [
+        ("name", service.name.as_str()),
+        ("type", service.unit_type()),
+        ("load", &service.load_state),
+        ("active", &service.active_state),
+        ("sub", &service.sub_state),
+        ("description", &service.description),
+        ("file_state", &service.unit_file_state),
+        ("preset", &service.vendor_preset),
+    ];
+
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("\"{key}\": {}", json_string(value)))
+        .collect();
+
+    format!("{{{}}}", fields.join(", "))
+}
+
+/// Tabs and line breaks in a field would shift the columns.
+fn tsv_field(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect()
+}
+
+impl OutputFormat {
+    /// Lines to print for the services.
+    pub fn format(self, services: &[Service], target: Target) -> Vec<String> {
+        match self {
+            Self::Plain => Listing::layout(services, target),
+            Self::Json => {
+                let last_idx = services.len().saturating_sub(1);
+                let mut lines = vec![String::from("[")];
+
+                for (idx, service) in services.iter().enumerate() {
+                    let separator = if idx == last_idx { "" } else { "," };
+                    lines.push(format!("  {}{separator}", json_object(service)));
+                }
+
+                lines.push(String::from("]"));
+                lines
+            }
+            Self::Tsv => {
+                let header = target.column_names().join("\t");
+                let rows = services.iter().map(|service| {
+                    let fields: Vec<String> = service
+                        .columns(target)
+                        .iter()
+                        .map(|field| tsv_field(field))
+                        .collect();
+
+                    fields.join("\t")
+                });
+
+                std::iter::once(header).chain(rows).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, description: &str) -> Service {
+        Service {
+            name: name.to_string(),
+            load_state: String::from("loaded"),
+            active_state: String::from("active"),
+            sub_state: String::from("running"),
+            description: description.to_string(),
+            ..Service::default()
+        }
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(json_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_string(r"C:\temp"), r#""C:\\temp""#);
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(
+            json_string("bell\u{7} esc\u{1b} del\u{7f}"),
+            r#""bell\u0007 esc\u001b del\u007f""#
+        );
+        assert_eq!(json_string("café ✓"), "\"café ✓\"");
+        assert_eq!(json_string(""), "null");
+    }
+
+    #[test]
+    fn prints_one_json_object_per_unit() {
+        let services = [
+            service("cron.service", "Regular \"background\" jobs"),
+            service("ssh.socket", ""),
+        ];
+
+        assert_eq!(
+            OutputFormat::Json.format(&services, Target::Memory),
+            [
+                "[",
+                r#"  {"name": "cron.service", "type": "service", "load": "loaded", "active": "active", "sub": "running", "description": "Regular \"background\" jobs", "file_state": null, "preset": null},"#,
+                r#"  {"name": "ssh.socket", "type": "socket", "load": "loaded", "active": "active", "sub": "running", "description": null, "file_state": null, "preset": null}"#,
+                "]",
+            ]
+        );
+        assert_eq!(OutputFormat::Json.format(&[], Target::Memory), ["[", "]"]);
+    }
+
+    #[test]
+    fn keeps_tsv_fields_on_their_column() {
+        assert_eq!(tsv_field("a\tb\nc\r\u{1b}d"), "a b c  d");
+
+        assert_eq!(
+            OutputFormat::Tsv.format(
+                &[service("cron.service", "Jobs\tand\ntimers")],
+                Target::Memory
+            ),
+            [
+                "unit\ttype\tload\tactive\tsub\tdescription",
+                "cron.service\tservice\tloaded\tactive\trunning\tJobs and timers",
+            ]
+        );
+    }
+}
@@ -0,0 +1,442 @@
+use crossterm::style::{Attributes, Color};
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+mod parser;
+
+use crate::{
+    systemd::{Escalation, Listing, OperationType, Signal},
+    tui::{
+        FilterMode, Keymap, QuickFilter, SearchOptions, SortOrder, Theme, parse_color, parse_effect,
+    },
+};
+use parser::{Entry, Value};
+
+const DEFAULT_REFRESH_SECONDS: u64 = 5;
+const DEFAULT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Behaviour that can be changed from the configuration file. Command line options take
+/// precedence over it.
+pub struct Config {
+    pub listing: Listing,
+    /// How often the unit list is refreshed, `None` when it is not.
+    pub refresh_interval: Option<Duration>,
+    /// How long messages stay in the message bar.
+    pub message_timeout: Duration,
+    /// How `systemctl` is run with privileges, ignored when running as root.
+    pub escalation: Escalation,
+    /// Operations that ask before they run.
+    pub confirm: Vec<OperationType>,
+    /// How the filter matches unit names at first.
+    pub filter_mode: FilterMode,
+    /// How search queries match at first.
+    pub search: SearchOptions,
+    /// Units shown at first, all when `None`.
+    pub quick_filter: Option<QuickFilter>,
+    /// How units are sorted at first.
+    pub sort: SortOrder,
+    pub keymap: Keymap,
+    pub theme: Theme,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            listing: Listing::default(),
+            refresh_interval: Some(Duration::from_secs(DEFAULT_REFRESH_SECONDS)),
+            message_timeout: DEFAULT_MESSAGE_TIMEOUT,
+            escalation: Escalation::default(),
+            confirm: vec![
+                OperationType::Stop,
+                OperationType::Disable,
+                OperationType::DisableNow,
+                OperationType::Mask,
+                OperationType::Kill(Signal::Term),
+            ],
+            filter_mode: FilterMode::default(),
+            search: SearchOptions::default(),
+            quick_filter: None,
+            sort: SortOrder::default(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+        }
+    }
+}
+
+fn string(entry: &Entry) -> Result<&str, String> {
+    match &entry.value {
+        Value::String(value) => Ok(value),
+        value => Err(format!(
+            "'{}' must be a string, not {}",
+            entry.key,
+            value.kind()
+        )),
+    }
+}
+
+fn boolean(entry: &Entry) -> Result<bool, String> {
+    match &entry.value {
+        Value::Boolean(value) => Ok(*value),
+        value => Err(format!(
+            "'{}' must be true or false, not {}",
+            entry.key,
+            value.kind()
+        )),
+    }
+}
+
+/// A single key, or a list of keys.
+fn keys(entry: &Entry) -> Result<Vec<String>, String> {
+    match &entry.value {
+        Value::String(key) => Ok(vec![key.clone()]),
+        Value::Array(keys) => Ok(keys.clone()),
+        value => Err(format!(
+            "'{}' must be a key or a list of keys, not {}",
+            entry.key,
+            value.kind()
+        )),
+    }
+}
+
+/// A color name, `#rrggbb`, `default` or a number of the 256 color palette.
+fn color(entry: &Entry) -> Result<Option<Color>, String> {
+    match &entry.value {
+        Value::String(value) => parse_color(value),
+        Value::Integer(value) => u8::try_from(*value)
+            .map(|value| Some(Color::AnsiValue(value)))
+            .map_err(|_err| format!("'{}' must be a color number up to 255", entry.key)),
+        value => Err(format!(
+            "'{}' must be a color, not {}",
+            entry.key,
+            value.kind()
+        )),
+    }
+}
+
+fn seconds(entry: &Entry) -> Result<u64, String> {
+    match &entry.value {
+        Value::Integer(value) => Ok(*value),
+        value => Err(format!(
+            "'{}' must be a number of seconds, not {}",
+            entry.key,
+            value.kind()
+        )),
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/systemctl-manager/config.toml`, where `XDG_CONFIG_HOME` defaults to
+    /// `~/.config`.
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_home.join("systemctl-manager").join("config.toml"))
+    }
+
+    /// Reads the configuration at `path`, or at the default path when `None`. Only a missing
+    /// file at the default path is fine, the defaults are used then.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, is_explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound && !is_explicit => {
+                return Ok(Self::default());
+            }
+            Err(err) => return Err(format!("Could not read {}: {err}", path.display())),
+        };
+
+        Self::parse(&text)
+            .map_err(|(line, message)| format!("{}:{line}: {message}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let entries = parser::parse(text)?;
+        let themes = Self::themes(&entries)?;
+        let mut config = Self::default();
+
+        for entry in entries
+            .iter()
+            .filter(|entry| !entry.section.starts_with("themes."))
+        {
+            config
+                .apply(entry, &themes)
+                .map_err(|message| (entry.line, message))?;
+        }
+
+        config.keymap.check()?;
+
+        Ok(config)
+    }
+
+    /// The themes defined in `[themes.<name>]` sections. Each one starts from its `base`
+    /// theme, dark by default, wherever `base` is in the section.
+    fn themes(entries: &[Entry]) -> Result<HashMap<String, Theme>, (usize, String)> {
+        let mut themes = HashMap::new();
+        let theme_entries = entries.iter().filter_map(|entry| {
+            entry
+                .section
+                .strip_prefix("themes.")
+                .map(|name| (name, entry))
+        });
+
+        for (name, entry) in theme_entries
+            .clone()
+            .filter(|(_, entry)| entry.key == "base")
+        {
+            let base = string(entry).map_err(|message| (entry.line, message))?;
+            let theme = Theme::named(base).ok_or_else(|| {
+                (
+                    entry.line,
+                    format!(
+                        "Unknown base theme '{base}', expected one of: {}",
+                        Theme::NAMES
+                    ),
+                )
+            })?;
+
+            themes.insert(name.to_string(), theme);
+        }
+
+        for (name, entry) in theme_entries.filter(|(_, entry)| entry.key != "base") {
+            let theme = themes.entry(name.to_string()).or_default();
+
+            Self::apply_theme(theme, entry).map_err(|message| (entry.line, message))?;
+        }
+
+        Ok(themes)
+    }
+
+    /// Sets `<part>_foreground`, `<part>_background` or `<part>_effects` of a theme.
+    fn apply_theme(theme: &mut Theme, entry: &Entry) -> Result<(), String> {
+        let Some((part, property)) = entry.key.rsplit_once('_') else {
+            return Err(format!("Unknown theme setting '{}'", entry.key));
+        };
+
+        match property {
+            "foreground" => theme.attribute_mut(part)?.foreground = color(entry)?,
+            "background" => theme.attribute_mut(part)?.background = color(entry)?,
+            "effects" => {
+                let Value::Array(names) = &entry.value else {
+                    return Err(format!("'{}' must be a list of effects", entry.key));
+                };
+
+                let mut effects = Attributes::none();
+
+                for name in names {
+                    effects.set(parse_effect(name)?);
+                }
+
+                theme.attribute_mut(part)?.effects = effects;
+            }
+            _ => return Err(format!("Unknown theme setting '{}'", entry.key)),
+        }
+
+        Ok(())
+    }
+
+    fn apply(&mut self, entry: &Entry, themes: &HashMap<String, Theme>) -> Result<(), String> {
+        if let Some(mode) = entry.section.strip_prefix("keys.") {
+            return self
+                .keymap
+                .bind(mode, &entry.key, &keys(entry)?, entry.line);
+        }
+
+        if !entry.section.is_empty() {
+            return Err(format!("Unknown section '{}'", entry.section));
+        }
+
+        match entry.key.as_str() {
+            "target" => self.listing.target = string(entry)?.parse()?,
+            "scope" => self.listing.scope = string(entry)?.parse()?,
+            "unit_type" => self.listing.unit_type = string(entry)?.parse()?,
+            "refresh_interval" => {
+                self.refresh_interval = match seconds(entry)? {
+                    0 => None,
+                    seconds => Some(Duration::from_secs(seconds)),
+                };
+            }
+            "message_timeout" => self.message_timeout = Duration::from_secs(seconds(entry)?),
+            "escalation" => self.escalation = string(entry)?.parse()?,
+            "confirm" => {
+                let Value::Array(names) = &entry.value else {
+                    return Err(String::from("'confirm' must be a list of operations"));
+                };
+
+                self.confirm = names
+                    .iter()
+                    .map(|name| name.parse())
+                    .collect::<Result<_, _>>()?;
+            }
+            "filter_mode" => self.filter_mode = string(entry)?.parse()?,
+            "search_regex" => self.search.regex = boolean(entry)?,
+            "search_match_case" => self.search.match_case = boolean(entry)?,
+            "search_whole_word" => self.search.whole_word = boolean(entry)?,
+            "quick_filter" => self.quick_filter = Some(string(entry)?.parse()?),
+            "sort" => self.sort.key = string(entry)?.parse()?,
+            "sort_descending" => self.sort.descending = boolean(entry)?,
+            "theme" => {
+                let name = string(entry)?;
+
+                self.theme = themes
+                    .get(name)
+                    .cloned()
+                    .or_else(|| Theme::named(name))
+                    .ok_or_else(|| {
+                        format!(
+                            "Unknown theme '{name}', expected one of: {} or a [themes.{name}] section",
+                            Theme::NAMES
+                        )
+                    })?;
+            }
+            key => return Err(format!("Unknown setting '{key}'")),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::{Scope, Target};
+
+    fn error(text: &str) -> (usize, String) {
+        Config::parse(text).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn applies_settings() {
+        let config = Config::parse(
+            r#"
+            target = "files"
+            scope = "user"
+            refresh_interval = 0
+            message_timeout = 10
+            confirm = ["stop", "kill"]
+            search_regex = true
+            sort_descending = true
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.listing.target == Target::Files);
+        assert!(config.listing.scope == Scope::User);
+        assert_eq!(config.refresh_interval, None);
+        assert_eq!(config.message_timeout, Duration::from_secs(10));
+        assert!(config.confirm == [OperationType::Stop, OperationType::Kill(Signal::Term)]);
+        assert!(config.search.regex);
+        assert!(config.sort.descending);
+    }
+
+    #[test]
+    fn names_confirmed_operations_like_actions() {
+        let config = Config::parse(r#"confirm = ["disable_now", "reset-failed"]"#).unwrap();
+
+        assert!(config.confirm == [OperationType::DisableNow, OperationType::ResetFailed]);
+        assert_eq!(
+            error(r#"confirm = ["stop", "halt"]"#),
+            (1, String::from("Unknown operation 'halt'"))
+        );
+    }
+
+    #[test]
+    fn starts_themes_from_their_base() {
+        let mut config = Config::parse(
+            "theme = \"mine\"\n[themes.mine]\nfailed_foreground = 196\nbase = \"light\"\n",
+        )
+        .unwrap();
+        let mut light = Theme::named("light").unwrap();
+
+        assert_eq!(
+            config.theme.attribute_mut("failed").unwrap().foreground,
+            Some(Color::AnsiValue(196))
+        );
+        assert_eq!(
+            config.theme.attribute_mut("active").unwrap().foreground,
+            light.attribute_mut("active").unwrap().foreground
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_settings_and_sections() {
+        assert_eq!(
+            error("target = \"files\"\n\nrefresh = 5"),
+            (3, String::from("Unknown setting 'refresh'"))
+        );
+        assert_eq!(
+            error("[colors]\nfailed = \"red\""),
+            (2, String::from("Unknown section 'colors'"))
+        );
+        assert_eq!(
+            error("[themes.mine]\nfailed_color = \"red\""),
+            (2, String::from("Unknown theme setting 'failed_color'"))
+        );
+        assert!(
+            error("theme = \"mine\"")
+                .1
+                .starts_with("Unknown theme 'mine'")
+        );
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        assert_eq!(
+            error("refresh_interval = \"5\""),
+            (
+                1,
+                String::from("'refresh_interval' must be a number of seconds, not a string")
+            )
+        );
+        assert_eq!(
+            error("\nsearch_regex = 1"),
+            (
+                2,
+                String::from("'search_regex' must be true or false, not a number")
+            )
+        );
+        assert_eq!(
+            error("scope = [\"user\"]"),
+            (1, String::from("'scope' must be a string, not a list"))
+        );
+        assert_eq!(
+            error("confirm = \"stop\""),
+            (1, String::from("'confirm' must be a list of operations"))
+        );
+        assert_eq!(
+            error("[keys.normal]\nstart = true"),
+            (
+                2,
+                String::from("'start' must be a key or a list of keys, not true or false")
+            )
+        );
+        assert_eq!(
+            error("[themes.mine]\nfailed_foreground = 256"),
+            (
+                2,
+                String::from("'failed_foreground' must be a color number up to 255")
+            )
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        assert_eq!(error("\n\nscope = user").0, 3);
+        assert_eq!(error("a = 1\na = 2").0, 2);
+        assert_eq!(error("\n\n\n[keys.normal]\nstart = \"j\"").0, 5);
+    }
+}
@@ -0,0 +1,291 @@
+/// A value on the right hand side of `key = value`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(u64),
+    Boolean(bool),
+    Array(Vec<String>),
+}
+
+impl Value {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::String(_) => "a string",
+            Self::Integer(_) => "a number",
+            Self::Boolean(_) => "true or false",
+            Self::Array(_) => "a list",
+        }
+    }
+}
+
+/// One `key = value` line, together with the section it is in and where it was found.
+pub struct Entry {
+    pub section: String,
+    pub key: String,
+    pub value: Value,
+    pub line: usize,
+}
+
+/// Reads a string that starts right after its opening quote, returns it and the rest of `text`.
+fn parse_string(text: &str) -> Result<(String, &str), String> {
+    let mut result = String::new();
+    let mut chars = text.char_indices();
+
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' => {
+                return Ok((
+                    result,
+                    text.get(idx.saturating_add(1)..).unwrap_or_default(),
+                ));
+            }
+            '\\' => match chars.next().map(|(_, escaped)| escaped) {
+                Some('"') => result.push('"'),
+                Some('\\') => result.push('\\'),
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(escaped) => return Err(format!("Unknown escape sequence '\\{escaped}'")),
+                None => break,
+            },
+            _ => result.push(ch),
+        }
+    }
+
+    Err(String::from("Missing closing quote"))
+}
+
+/// Comments start with `#` outside of strings.
+fn expect_end(rest: &str) -> Result<(), String> {
+    let rest = rest.trim_start();
+
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(())
+    } else {
+        Err(format!("Unexpected '{rest}' after the value"))
+    }
+}
+
+fn parse_array(text: &str) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+    let mut rest = text.trim_start();
+
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            expect_end(after)?;
+            return Ok(items);
+        }
+
+        let Some(after_quote) = rest.strip_prefix('"') else {
+            return Err(String::from("Lists can only hold quoted strings"));
+        };
+
+        let (item, after) = parse_string(after_quote)?;
+        items.push(item);
+        rest = after.trim_start();
+
+        if let Some(after_comma) = rest.strip_prefix(',') {
+            rest = after_comma.trim_start();
+        } else if !rest.starts_with(']') {
+            return Err(String::from("Expected ',' or ']' in the list"));
+        }
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if let Some(after_quote) = text.strip_prefix('"') {
+        let (value, rest) = parse_string(after_quote)?;
+        expect_end(rest)?;
+
+        return Ok(Value::String(value));
+    }
+
+    if let Some(after_bracket) = text.strip_prefix('[') {
+        return parse_array(after_bracket).map(Value::Array);
+    }
+
+    let word = text.split('#').next().unwrap_or_default().trim();
+
+    match word {
+        "" => Err(String::from("Missing value")),
+        "true" => Ok(Value::Boolean(true)),
+        "false" => Ok(Value::Boolean(false)),
+        _ => word
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_err| format!("Invalid value '{word}', strings need quotes")),
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+}
+
+/// Parses the TOML subset the configuration uses: `[section]` headers, `key = value` lines with
+/// strings, numbers, booleans and lists of strings as values, and `#` comments.
+/// Errors are reported as `line: message`, lines counting from 1.
+pub fn parse(text: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut section = String::new();
+
+    for (idx, raw_line) in text.lines().enumerate() {
+        let line = idx.saturating_add(1);
+        let trimmed = raw_line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let Some((name, rest)) = header.split_once(']') else {
+                return Err((line, String::from("Missing ']' after the section name")));
+            };
+            let name = name.trim();
+
+            expect_end(rest).map_err(|message| (line, message))?;
+
+            if !is_valid_name(name) {
+                return Err((line, format!("Invalid section name '{name}'")));
+            }
+
+            section = name.to_string();
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once('=') else {
+            return Err((line, String::from("Expected 'key = value'")));
+        };
+        let key = key.trim();
+
+        if !is_valid_name(key) {
+            return Err((line, format!("Invalid key '{key}'")));
+        }
+
+        if entries
+            .iter()
+            .any(|entry| entry.section == section && entry.key == key)
+        {
+            return Err((line, format!("'{key}' is set twice")));
+        }
+
+        let value = parse_value(value.trim()).map_err(|message| (line, message))?;
+
+        entries.push(Entry {
+            section: section.clone(),
+            key: key.to_string(),
+            value,
+            line,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(text: &str) -> Value {
+        let entries = parse(&format!("key = {text}")).unwrap();
+
+        entries.into_iter().next().unwrap().value
+    }
+
+    fn error(text: &str) -> (usize, String) {
+        parse(text).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(value("5"), Value::Integer(5));
+        assert_eq!(value("true"), Value::Boolean(true));
+        assert_eq!(value("false # off"), Value::Boolean(false));
+        assert_eq!(value(r#""fuzzy""#), Value::String(String::from("fuzzy")));
+        assert_eq!(value(r#""""#), Value::String(String::new()));
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        assert_eq!(
+            value(r#""a \"b\" \\ c\td\n""#),
+            Value::String(String::from("a \"b\" \\ c\td\n"))
+        );
+        assert_eq!(value(r##""#1""##), Value::String(String::from("#1")));
+        assert_eq!(error(r#"key = "\x""#).1, "Unknown escape sequence '\\x'");
+        assert_eq!(error(r#"key = "open"#).1, "Missing closing quote");
+        assert_eq!(error(r#"key = "a\"#).1, "Missing closing quote");
+    }
+
+    #[test]
+    fn parses_arrays() {
+        let array = |items: &[&str]| Value::Array(items.iter().map(ToString::to_string).collect());
+
+        assert_eq!(value("[]"), array(&[]));
+        assert_eq!(value(r#"[ "a" ,"b,c"]  # comment"#), array(&["a", "b,c"]));
+        assert_eq!(value(r#"["a", "b", ]"#), array(&["a", "b"]));
+        assert_eq!(error("key = [1]").1, "Lists can only hold quoted strings");
+        assert_eq!(
+            error(r#"key = ["a" "b"]"#).1,
+            "Expected ',' or ']' in the list"
+        );
+        assert_eq!(error(r#"key = ["a""#).1, "Expected ',' or ']' in the list");
+        assert_eq!(
+            error(r#"key = ["a"] x"#).1,
+            "Unexpected 'x' after the value"
+        );
+    }
+
+    #[test]
+    fn keeps_sections_and_lines() {
+        let entries = parse(
+            "# comment\nrefresh_interval = 2\n\n[keys.normal]  # bindings\n  start = \"g\"\n[themes.mine]\nbase = \"light\"\n",
+        )
+        .unwrap();
+        let found: Vec<(&str, &str, usize)> = entries
+            .iter()
+            .map(|entry| (entry.section.as_str(), entry.key.as_str(), entry.line))
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                ("", "refresh_interval", 2),
+                ("keys.normal", "start", 5),
+                ("themes.mine", "base", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_keys_set_twice_in_the_same_section() {
+        assert_eq!(
+            error("a = 1\n[keys.normal]\na = \"x\"\n\na = \"y\""),
+            (5, String::from("'a' is set twice"))
+        );
+        assert!(parse("a = 1\n[other]\na = 1").is_ok());
+    }
+
+    #[test]
+    fn reports_the_line_of_syntax_errors() {
+        assert_eq!(
+            error("a = 1\n[keys"),
+            (2, String::from("Missing ']' after the section name"))
+        );
+        assert_eq!(
+            error("\n\n[a b]"),
+            (3, String::from("Invalid section name 'a b'"))
+        );
+        assert_eq!(
+            error("a = 1\nb"),
+            (2, String::from("Expected 'key = value'"))
+        );
+        assert_eq!(error("a b = 1"), (1, String::from("Invalid key 'a b'")));
+        assert_eq!(error("a =  # none"), (1, String::from("Missing value")));
+        assert_eq!(
+            error("a = 1\ntheme = dark"),
+            (2, String::from("Invalid value 'dark', strings need quotes"))
+        );
+    }
+}
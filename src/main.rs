@@ -6,11 +6,55 @@
     clippy::as_conversions,
     clippy::integer_division
 )]
+mod args;
+mod cli;
+mod config;
 mod prelude;
+mod systemd;
 mod tui;
 
+use std::{env, process::exit};
+
+use args::{Args, USAGE};
+use config::Config;
 use tui::Tui;
 
+#[allow(clippy::print_stdout)]
 fn main() {
-    Tui::new().unwrap().run();
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            exit(2);
+        }
+    };
+
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    let mut config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Invalid configuration: {err}");
+            exit(2);
+        }
+    };
+
+    args.apply(&mut config);
+
+    let backend = match args.backend.create(config.listing.scope, config.escalation) {
+        Ok(backend) => backend,
+        Err(err) => {
+            eprintln!("Could not start the backend: {err}");
+            exit(1);
+        }
+    };
+
+    if let Some(subcommand) = &args.subcommand {
+        exit(cli::run(subcommand, backend, &config));
+    }
+
+    Tui::new(backend, &config).unwrap().run();
 }
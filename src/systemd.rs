@@ -0,0 +1,19 @@
+mod backend;
+mod escalation;
+mod journal;
+mod listing;
+mod operation;
+mod scope;
+mod service;
+mod signal;
+mod unittype;
+
+pub use backend::{Backend, BackendKind, Properties};
+pub use escalation::{Escalation, is_root};
+pub use journal::{Journal, JournalQuery, Priority, TimeRange};
+pub use listing::{Listing, Target};
+pub use operation::{Operation, OperationOutput, OperationType};
+pub use scope::Scope;
+pub use service::Service;
+pub use signal::Signal;
+pub use unittype::UnitType;
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, fmt::Write, io::Error, str::FromStr};
+
+#[cfg(feature = "dbus")]
+mod dbus;
+mod fake;
+mod journalctl;
+mod systemctl;
+
+use super::{
+    Escalation, Journal, JournalQuery, Operation, OperationOutput, Scope, Service, UnitType,
+};
+#[cfg(feature = "dbus")]
+use dbus::{Bus, DBus};
+use fake::Fake;
+use systemctl::Systemctl;
+
+/// Unit properties as reported by `systemctl show --timestamp=unix`, keyed by property name.
+pub type Properties = HashMap<String, String>;
+
+/// Short, `systemctl status` like description of a unit, for backends that can not ask systemctl.
+fn describe(properties: &Properties) -> String {
+    let property = |key: &str| properties.get(key).map_or("", String::as_str);
+    let mut status = String::new();
+
+    let _ = writeln!(status, "{} - {}", property("Id"), property("Description"));
+    let _ = writeln!(
+        status,
+        "     Loaded: {} ({})",
+        property("LoadState"),
+        property("UnitFileState")
+    );
+    let _ = writeln!(
+        status,
+        "     Active: {} ({})",
+        property("ActiveState"),
+        property("SubState")
+    );
+
+    status
+}
+
+/// Takes the memory use, activation time, unit file state and vendor preset of `service` from
+/// its properties.
+fn apply_details(service: &mut Service, properties: &Properties) {
+    let property = |key: &str| properties.get(key).map_or("", String::as_str);
+
+    // Memory that is not accounted is `[not set]`, or the largest value on older versions.
+    service.memory = property("MemoryCurrent")
+        .parse()
+        .ok()
+        .filter(|memory| *memory != u64::MAX);
+    service.active_since = property("ActiveEnterTimestamp")
+        .strip_prefix('@')
+        .and_then(|seconds| seconds.parse().ok());
+
+    if service.unit_file_state.is_empty() {
+        service.unit_file_state = property("UnitFileState").to_string();
+    }
+
+    if service.vendor_preset.is_empty() {
+        service.vendor_preset = property("UnitFilePreset").to_string();
+    }
+}
+
+/// Lists and controls units. Implementations must not touch the terminal,
+/// the caller is responsible for handing it over when `may_prompt` says so.
+pub trait Backend {
+    /// Units currently loaded in memory, see `systemctl list-units --all`.
+    fn list_units(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error>;
+
+    /// Units installed on disk, see `systemctl list-unit-files`.
+    fn list_unit_files(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error>;
+
+    /// Whether executing `operations` may ask for credentials on the controlling terminal.
+    /// Asked once for the whole batch, as finding out may take a command of its own.
+    fn may_prompt(&self, operations: &[Operation]) -> bool;
+
+    /// How executing `operation` looks on the command line, to show before it runs.
+    fn command_line(&self, operation: &Operation) -> String;
+
+    fn execute(&self, operation: &Operation) -> Result<OperationOutput, Error>;
+
+    fn properties(&self, scope: Scope, name: &str) -> Result<Properties, Error>;
+
+    /// Fills in the memory use, activation time, unit file state and vendor preset of loaded
+    /// `services`, which listing them does not report.
+    fn fill_details(&self, scope: Scope, services: &mut [Service]) -> Result<(), Error> {
+        for service in services {
+            apply_details(service, &self.properties(scope, &service.name)?);
+        }
+
+        Ok(())
+    }
+
+    /// Journal entries of a unit, oldest first.
+    fn journal(&self, scope: Scope, query: &JournalQuery) -> Result<Journal, Error>;
+}
+
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum BackendKind {
+    #[default]
+    Systemctl,
+    #[cfg(feature = "dbus")]
+    DBus,
+    #[cfg(feature = "dbus")]
+    DBusSession,
+    Fake,
+}
+
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "systemctl" => Ok(Self::Systemctl),
+            #[cfg(feature = "dbus")]
+            "dbus" => Ok(Self::DBus),
+            #[cfg(feature = "dbus")]
+            "dbus-session" => Ok(Self::DBusSession),
+            "fake" => Ok(Self::Fake),
+            _ => Err(format!(
+                "Unknown backend '{value}', expected one of: {}",
+                Self::NAMES
+            )),
+        }
+    }
+}
+
+impl BackendKind {
+    #[cfg(feature = "dbus")]
+    pub const NAMES: &str = "systemctl, dbus, dbus-session, fake";
+    #[cfg(not(feature = "dbus"))]
+    pub const NAMES: &str = "systemctl, fake";
+
+    /// Creates the backend, `scope` is the manager it is going to be used with first and
+    /// `escalation` how `systemctl` gets privileges when needed.
+    #[cfg_attr(
+        not(feature = "dbus"),
+        allow(clippy::unnecessary_wraps, unused_variables)
+    )]
+    pub fn create(self, scope: Scope, escalation: Escalation) -> Result<Box<dyn Backend>, Error> {
+        Ok(match self {
+            Self::Systemctl => Box::new(Systemctl::new(escalation)),
+            #[cfg(feature = "dbus")]
+            Self::DBus => Box::new(DBus::connect(Bus::System, scope)?),
+            #[cfg(feature = "dbus")]
+            Self::DBusSession => Box::new(DBus::connect(Bus::Session, scope)?),
+            Self::Fake => Box::new(Fake::default()),
+        })
+    }
+}
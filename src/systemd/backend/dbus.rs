@@ -0,0 +1,738 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{Error, Read, pipe},
+    process::{self, Child, Command},
+};
+
+use zbus::{
+    blocking::{Connection, Proxy},
+    proxy::MethodFlags,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+use super::{
+    super::{
+        Journal, JournalQuery, Operation, OperationOutput, OperationType, Scope, Service, UnitType,
+        is_root,
+    },
+    Backend, Properties, describe, journalctl,
+};
+
+const DESTINATION: &str = "org.freedesktop.systemd1";
+const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+/// Reply of `ListUnits`: name, description, load state, active state, sub state, followed unit,
+/// unit path, job id, job type and job path.
+type UnitEntry = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+/// Which message bus the system manager is reached on.
+/// The user manager always lives on the session bus.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum Bus {
+    System,
+    /// The session bus, where a stub manager can be registered for testing.
+    Session,
+}
+
+/// Talks to systemd through its D-Bus API instead of spawning `systemctl`.
+/// Privileged calls allow interactive polkit authorization instead of going through `sudo`.
+pub struct DBus {
+    bus: Bus,
+    connections: RefCell<HashMap<Bus, Connection>>,
+}
+
+/// Text mode polkit agent registered for this process while alive, so authorization
+/// prompts show up on the terminal when no graphical agent is running.
+struct TtyAgent {
+    child: Child,
+}
+
+impl TtyAgent {
+    fn spawn() -> Option<Self> {
+        let (mut reader, writer) = pipe().ok()?;
+        let mut command = Command::new("pkttyagent");
+        command
+            .args(["--process", &process::id().to_string()])
+            .args(["--notify-fd", "1", "--fallback"])
+            .stdout(writer);
+
+        let child = command.spawn().ok()?;
+
+        // The command still owns the write end, it has to go for the read below to see the agent exit.
+        drop(command);
+
+        // The agent writes to the notify fd once it is registered.
+        let _ = reader.read(&mut [0; 1]);
+
+        Some(Self { child })
+    }
+}
+
+impl Drop for TtyAgent {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn to_error(err: &zbus::Error) -> Error {
+    Error::other(describe_error(err))
+}
+
+fn describe_error(err: &zbus::Error) -> String {
+    match err {
+        zbus::Error::MethodError(_, Some(description), _) => description.clone(),
+        zbus::Error::MethodError(name, None, _) => name.to_string(),
+        _ => err.to_string(),
+    }
+}
+
+/// Whether polkit refused the call, as opposed to systemd failing it.
+fn is_denied(err: &zbus::Error) -> bool {
+    matches!(
+        err,
+        zbus::Error::MethodError(name, ..) if matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.AccessDenied"
+                | "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired"
+        )
+    )
+}
+
+/// Formats a property value the way `systemctl show --timestamp=unix` does.
+fn value_to_string(key: &str, value: &Value<'_>) -> String {
+    match value {
+        Value::U64(u64::MAX) => String::from("[not set]"),
+        Value::U64(0) if key.ends_with("Timestamp") => String::new(),
+        Value::U64(microseconds) if key.ends_with("Timestamp") => {
+            format!("@{}", microseconds.saturating_div(1_000_000))
+        }
+        Value::U8(number) => number.to_string(),
+        Value::Bool(true) => String::from("yes"),
+        Value::Bool(false) => String::from("no"),
+        Value::I16(number) => number.to_string(),
+        Value::U16(number) => number.to_string(),
+        Value::I32(number) => number.to_string(),
+        Value::U32(number) => number.to_string(),
+        Value::I64(number) => number.to_string(),
+        Value::U64(number) => number.to_string(),
+        Value::F64(number) => number.to_string(),
+        Value::Str(string) => string.to_string(),
+        Value::ObjectPath(path) => path.to_string(),
+        Value::Value(inner) => value_to_string(key, inner),
+        Value::Array(array) => array
+            .iter()
+            .map(|item| value_to_string(key, item))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => value.to_string(),
+    }
+}
+
+/// Type specific interface of a unit, e.g. `org.freedesktop.systemd1.Service` for `foo.service`.
+fn type_interface(name: &str) -> Option<String> {
+    let (_, suffix) = name.rsplit_once('.')?;
+    let mut chars = suffix.chars();
+    let first = chars.next()?.to_ascii_uppercase();
+
+    Some(format!(
+        "org.freedesktop.systemd1.{first}{}",
+        chars.as_str()
+    ))
+}
+
+impl DBus {
+    /// Connects to the manager of `scope` right away, so an unreachable bus is reported on startup.
+    pub fn connect(bus: Bus, scope: Scope) -> Result<Self, Error> {
+        let dbus = Self {
+            bus,
+            connections: RefCell::new(HashMap::new()),
+        };
+
+        let _ = dbus.connection(scope).map_err(|err| to_error(&err))?;
+
+        Ok(dbus)
+    }
+
+    fn connection(&self, scope: Scope) -> zbus::Result<Connection> {
+        let bus = if scope == Scope::User {
+            Bus::Session
+        } else {
+            self.bus
+        };
+
+        if let Some(connection) = self.connections.borrow().get(&bus) {
+            return Ok(connection.clone());
+        }
+
+        let connection = match bus {
+            Bus::System => Connection::system(),
+            Bus::Session => Connection::session(),
+        }?;
+
+        self.connections
+            .borrow_mut()
+            .insert(bus, connection.clone());
+
+        Ok(connection)
+    }
+
+    fn manager(&self, scope: Scope) -> zbus::Result<Proxy<'static>> {
+        Proxy::new(
+            &self.connection(scope)?,
+            DESTINATION,
+            MANAGER_PATH,
+            MANAGER_INTERFACE,
+        )
+    }
+
+    fn call_interactive<B, R>(proxy: &Proxy<'_>, method: &str, body: &B) -> zbus::Result<R>
+    where
+        B: zbus::export::serde::ser::Serialize + zbus::zvariant::DynamicType,
+        R: for<'d> zbus::zvariant::DynamicDeserialize<'d>,
+    {
+        proxy
+            .call_with_flags(method, MethodFlags::AllowInteractiveAuth.into(), body)?
+            .ok_or_else(|| zbus::Error::Failure(format!("{method} returned no reply")))
+    }
+
+    /// Queues a job through `method` and waits for systemd to finish it.
+    fn run_job(&self, scope: Scope, method: &str, name: &str) -> zbus::Result<Result<(), String>> {
+        let manager = self.manager(scope)?;
+
+        // Subscribe before queueing the job, so its removal cannot be missed.
+        manager.call::<_, _, ()>("Subscribe", &())?;
+        let result = Self::wait_for_job(&manager, method, name);
+
+        // Otherwise systemd keeps sending signals about every unit to this connection. The job
+        // is over either way, so failing to unsubscribe does not change its result.
+        let _ = manager.call::<_, _, ()>("Unsubscribe", &());
+
+        result
+    }
+
+    /// Queues the job on a manager subscribed to its signals and waits for its removal.
+    fn wait_for_job(
+        manager: &Proxy<'_>,
+        method: &str,
+        name: &str,
+    ) -> zbus::Result<Result<(), String>> {
+        let mut job_removed = manager.receive_signal("JobRemoved")?;
+
+        let job: OwnedObjectPath = Self::call_interactive(manager, method, &(name, "replace"))?;
+
+        for message in &mut job_removed {
+            let (_id, path, unit, result): (u32, OwnedObjectPath, String, String) =
+                message.body().deserialize()?;
+
+            if path == job {
+                return Ok(if result == "done" {
+                    Ok(())
+                } else {
+                    Err(format!("Job for {unit} failed with result '{result}'."))
+                });
+            }
+        }
+
+        Err(zbus::Error::Failure(String::from(
+            "Connection closed while waiting for the job",
+        )))
+    }
+
+    fn change_unit_file(
+        &self,
+        scope: Scope,
+        operation_type: OperationType,
+        name: &str,
+    ) -> zbus::Result<()> {
+        let manager = self.manager(scope)?;
+
+        match operation_type {
+            OperationType::Enable | OperationType::EnableNow => {
+                let _: (bool, Vec<(String, String, String)>) =
+                    Self::call_interactive(&manager, "EnableUnitFiles", &(&[name], false, false))?;
+            }
+            OperationType::Mask => {
+                let _: Vec<(String, String, String)> =
+                    Self::call_interactive(&manager, "MaskUnitFiles", &(&[name], false, false))?;
+            }
+            OperationType::Unmask => {
+                let _: Vec<(String, String, String)> =
+                    Self::call_interactive(&manager, "UnmaskUnitFiles", &(&[name], false))?;
+            }
+            _ => {
+                let _: Vec<(String, String, String)> =
+                    Self::call_interactive(&manager, "DisableUnitFiles", &(&[name], false))?;
+            }
+        }
+
+        Self::call_interactive::<_, ()>(&manager, "Reload", &())
+    }
+
+    fn run(&self, operation: &Operation) -> zbus::Result<Result<String, String>> {
+        let name = operation.name.as_str();
+        let scope = operation.scope;
+        let job = |method| {
+            self.run_job(scope, method, name)
+                .map(|result| result.map(|()| String::new()))
+        };
+
+        match operation.operation_type {
+            OperationType::Status => self
+                .unit_properties(scope, name)
+                .map(|properties| Ok(describe(&properties))),
+            OperationType::Start => job("StartUnit"),
+            OperationType::Stop => job("StopUnit"),
+            OperationType::Reload => job("ReloadUnit"),
+            OperationType::Restart => job("RestartUnit"),
+            OperationType::ReloadOrRestart => job("ReloadOrRestartUnit"),
+            OperationType::TryRestart => job("TryRestartUnit"),
+            OperationType::Enable
+            | OperationType::Disable
+            | OperationType::Mask
+            | OperationType::Unmask => self
+                .change_unit_file(scope, operation.operation_type, name)
+                .map(|()| Ok(String::new())),
+            OperationType::EnableNow => {
+                self.change_unit_file(scope, operation.operation_type, name)?;
+                job("StartUnit")
+            }
+            OperationType::DisableNow => {
+                self.change_unit_file(scope, operation.operation_type, name)?;
+                job("StopUnit")
+            }
+            OperationType::Kill(signal) => Self::call_interactive::<_, ()>(
+                &self.manager(scope)?,
+                "KillUnit",
+                &(name, "all", signal.number()),
+            )
+            .map(|()| Ok(String::new())),
+            OperationType::ResetFailed => {
+                Self::call_interactive::<_, ()>(&self.manager(scope)?, "ResetFailedUnit", &(name,))
+                    .map(|()| Ok(String::new()))
+            }
+            OperationType::DaemonReload => {
+                Self::call_interactive::<_, ()>(&self.manager(scope)?, "Reload", &())
+                    .map(|()| Ok(String::new()))
+            }
+        }
+    }
+
+    fn unit_properties(&self, scope: Scope, name: &str) -> zbus::Result<Properties> {
+        let path: OwnedObjectPath = self.manager(scope)?.call("LoadUnit", &(name,))?;
+        let proxy = Proxy::new(
+            &self.connection(scope)?,
+            DESTINATION,
+            &path,
+            PROPERTIES_INTERFACE,
+        )?;
+        let mut properties = Properties::new();
+
+        for interface in [Some(String::from(UNIT_INTERFACE)), type_interface(name)]
+            .into_iter()
+            .flatten()
+        {
+            let Ok(values) =
+                proxy.call::<_, _, HashMap<String, OwnedValue>>("GetAll", &(&interface,))
+            else {
+                continue;
+            };
+
+            for (key, value) in values {
+                let value = value_to_string(&key, &value);
+                properties.insert(key, value);
+            }
+        }
+
+        Ok(properties)
+    }
+}
+
+impl Backend for DBus {
+    fn list_units(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        let units: Vec<UnitEntry> = self
+            .manager(scope)
+            .and_then(|manager| manager.call("ListUnits", &()))
+            .map_err(|err| to_error(&err))?;
+
+        Ok(units
+            .into_iter()
+            .filter(|unit| unit_type.matches(&unit.0))
+            .map(
+                |(name, description, load_state, active_state, sub_state, ..)| Service {
+                    name,
+                    load_state,
+                    active_state,
+                    sub_state,
+                    description,
+                    ..Service::default()
+                },
+            )
+            .collect())
+    }
+
+    fn list_unit_files(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        let files: Vec<(String, String)> = self
+            .manager(scope)
+            .and_then(|manager| manager.call("ListUnitFiles", &()))
+            .map_err(|err| to_error(&err))?;
+
+        Ok(files
+            .into_iter()
+            .filter_map(|(path, unit_file_state)| {
+                let (_, name) = path.rsplit_once('/')?;
+
+                unit_type.matches(name).then(|| Service {
+                    name: name.to_string(),
+                    unit_file_state,
+                    ..Service::default()
+                })
+            })
+            .collect())
+    }
+
+    /// Root is authorized by polkit without asking.
+    fn may_prompt(&self, operations: &[Operation]) -> bool {
+        operations.iter().any(Operation::needs_privileges) && !is_root()
+    }
+
+    /// The `busctl` call doing the same as the method call behind `operation`.
+    fn command_line(&self, operation: &Operation) -> String {
+        let bus = if operation.scope == Scope::User || self.bus == Bus::Session {
+            " --user"
+        } else {
+            ""
+        };
+        let name = &operation.name;
+        let prefix = format!("busctl{bus} call {DESTINATION} {MANAGER_PATH} {MANAGER_INTERFACE}");
+        let call = match operation.operation_type {
+            OperationType::Status => format!("LoadUnit s {name}"),
+            OperationType::Start => format!("StartUnit ss {name} replace"),
+            OperationType::Stop => format!("StopUnit ss {name} replace"),
+            OperationType::Reload => format!("ReloadUnit ss {name} replace"),
+            OperationType::Restart => format!("RestartUnit ss {name} replace"),
+            OperationType::Enable => format!("EnableUnitFiles asbb 1 {name} false false"),
+            OperationType::Disable => format!("DisableUnitFiles asb 1 {name} false"),
+            OperationType::EnableNow => format!(
+                "EnableUnitFiles asbb 1 {name} false false && {prefix} StartUnit ss {name} replace"
+            ),
+            OperationType::DisableNow => format!(
+                "DisableUnitFiles asb 1 {name} false && {prefix} StopUnit ss {name} replace"
+            ),
+            OperationType::ReloadOrRestart => format!("ReloadOrRestartUnit ss {name} replace"),
+            OperationType::TryRestart => format!("TryRestartUnit ss {name} replace"),
+            OperationType::Mask => format!("MaskUnitFiles asbb 1 {name} false false"),
+            OperationType::Unmask => format!("UnmaskUnitFiles asb 1 {name} false"),
+            OperationType::Kill(signal) => format!("KillUnit ssi {name} all {}", signal.number()),
+            OperationType::ResetFailed => format!("ResetFailedUnit s {name}"),
+            OperationType::DaemonReload => String::from("Reload"),
+        };
+
+        format!("{prefix} {call}")
+    }
+
+    fn execute(&self, operation: &Operation) -> Result<OperationOutput, Error> {
+        let _agent = if self.may_prompt(std::slice::from_ref(operation)) {
+            TtyAgent::spawn()
+        } else {
+            None
+        };
+
+        let (result, denied) = match self.run(operation) {
+            Ok(result) => (result, false),
+            Err(err) => (Err(describe_error(&err)), is_denied(&err)),
+        };
+
+        Ok(match result {
+            Ok(stdout) => OperationOutput {
+                success: true,
+                denied: false,
+                stdout,
+                stderr: String::new(),
+            },
+            Err(stderr) => OperationOutput {
+                success: false,
+                denied,
+                stdout: String::new(),
+                stderr,
+            },
+        })
+    }
+
+    fn properties(&self, scope: Scope, name: &str) -> Result<Properties, Error> {
+        self.unit_properties(scope, name)
+            .map_err(|err| to_error(&err))
+    }
+
+    fn journal(&self, scope: Scope, query: &JournalQuery) -> Result<Journal, Error> {
+        journalctl::read(scope, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        process::Stdio,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use zbus::{blocking::connection, object_server::SignalEmitter, zvariant::ObjectPath};
+
+    use super::*;
+
+    /// A private session bus, stopped when dropped.
+    struct TestBus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl TestBus {
+        /// `None` when `dbus-daemon` is not installed.
+        fn start() -> Option<Self> {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.as_mut()?)
+                .read_line(&mut address)
+                .ok()?;
+
+            Some(Self {
+                daemon,
+                address: address.trim().to_string(),
+            })
+        }
+
+        fn connect(&self) -> connection::Builder<'static> {
+            connection::Builder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for TestBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    /// Stands in for the manager of systemd, with the clients subscribed to its signals.
+    struct StubManager {
+        subscribed: Arc<AtomicUsize>,
+    }
+
+    fn unit_path(name: &str) -> OwnedObjectPath {
+        let path = format!("{MANAGER_PATH}/unit/{}", name.replace(['.', '-'], "_"));
+
+        ObjectPath::try_from(path).unwrap().into()
+    }
+
+    // clippy::unused_self: D-Bus methods and properties are served from `&self`.
+    #[allow(clippy::unused_self)]
+    #[zbus::interface(name = "org.freedesktop.systemd1.Manager")]
+    impl StubManager {
+        /// Not sorted by name, as systemd does not either.
+        fn list_units(&self) -> Vec<UnitEntry> {
+            [
+                ("nginx.service", "failed"),
+                ("cron.service", "active"),
+                ("ssh.socket", "active"),
+            ]
+            .into_iter()
+            .map(|(name, active_state)| {
+                (
+                    name.to_string(),
+                    format!("The {name} unit"),
+                    String::from("loaded"),
+                    active_state.to_string(),
+                    active_state.to_string(),
+                    String::new(),
+                    unit_path(name),
+                    0,
+                    String::new(),
+                    OwnedObjectPath::from(ObjectPath::from_static_str_unchecked("/")),
+                )
+            })
+            .collect()
+        }
+
+        fn list_unit_files(&self) -> Vec<(String, String)> {
+            vec![
+                (
+                    String::from("/lib/systemd/system/nginx.service"),
+                    String::from("disabled"),
+                ),
+                (
+                    String::from("/etc/systemd/system/cron.service"),
+                    String::from("enabled"),
+                ),
+            ]
+        }
+
+        fn load_unit(&self, name: &str) -> OwnedObjectPath {
+            unit_path(name)
+        }
+
+        fn subscribe(&self) {
+            self.subscribed.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn unsubscribe(&self) {
+            self.subscribed.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        /// Finishes the job right away. It fails for `nginx.service`, and for any unit when
+        /// the job would not replace queued ones.
+        async fn start_unit(
+            &self,
+            name: &str,
+            mode: &str,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let job = OwnedObjectPath::from(ObjectPath::from_static_str_unchecked(
+                "/org/freedesktop/systemd1/job/1",
+            ));
+            let result = if name == "nginx.service" || mode != "replace" {
+                "failed"
+            } else {
+                "done"
+            };
+
+            Self::job_removed(&emitter, 1, job.as_ref(), name, result).await?;
+
+            Ok(job)
+        }
+
+        #[zbus(signal)]
+        async fn job_removed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            job: ObjectPath<'_>,
+            unit: &str,
+            result: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    struct StubUnit {
+        name: String,
+    }
+
+    // clippy::unused_self: D-Bus methods and properties are served from `&self`.
+    #[allow(clippy::unused_self)]
+    #[zbus::interface(name = "org.freedesktop.systemd1.Unit")]
+    impl StubUnit {
+        #[zbus(property)]
+        fn id(&self) -> String {
+            self.name.clone()
+        }
+
+        #[zbus(property)]
+        fn active_enter_timestamp(&self) -> u64 {
+            1_700_000_000_000_000
+        }
+
+        #[zbus(property)]
+        fn unit_file_state(&self) -> String {
+            String::from("enabled")
+        }
+    }
+
+    /// A backend talking to a stub manager on a private bus, and how many clients are
+    /// subscribed to the manager.
+    fn connect(bus: &TestBus) -> (DBus, Connection, Arc<AtomicUsize>) {
+        let subscribed = Arc::new(AtomicUsize::new(0));
+        let service = bus
+            .connect()
+            .name(DESTINATION)
+            .unwrap()
+            .serve_at(
+                MANAGER_PATH,
+                StubManager {
+                    subscribed: Arc::clone(&subscribed),
+                },
+            )
+            .unwrap()
+            .serve_at(
+                unit_path("cron.service"),
+                StubUnit {
+                    name: String::from("cron.service"),
+                },
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let client = bus.connect().build().unwrap();
+        let dbus = DBus {
+            bus: Bus::Session,
+            connections: RefCell::new(HashMap::from([(Bus::Session, client)])),
+        };
+
+        (dbus, service, subscribed)
+    }
+
+    fn start(dbus: &DBus, name: &str) -> OperationOutput {
+        dbus.execute(&Operation {
+            operation_type: OperationType::Start,
+            name: name.to_string(),
+            scope: Scope::User,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn talks_to_a_stub_manager() {
+        let Some(bus) = TestBus::start() else {
+            return;
+        };
+        let (dbus, _service, subscribed) = connect(&bus);
+
+        let units = dbus.list_units(Scope::User, UnitType::Service).unwrap();
+        let names: Vec<&str> = units.iter().map(|unit| unit.name.as_str()).collect();
+        assert_eq!(names, ["nginx.service", "cron.service"]);
+        assert_eq!(units[0].active_state, "failed");
+        assert_eq!(units[1].description, "The cron.service unit");
+
+        let files = dbus.list_unit_files(Scope::User, UnitType::All).unwrap();
+        assert_eq!(files[0].name, "nginx.service");
+        assert_eq!(files[1].unit_file_state, "enabled");
+
+        let mut cron = units[1].clone();
+        dbus.fill_details(Scope::User, std::slice::from_mut(&mut cron))
+            .unwrap();
+        assert_eq!(cron.active_since, Some(1_700_000_000));
+        assert_eq!(cron.unit_file_state, "enabled");
+        assert_eq!(cron.memory, None);
+
+        assert!(start(&dbus, "cron.service").success);
+        assert_eq!(subscribed.load(Ordering::SeqCst), 0);
+
+        let failed = start(&dbus, "nginx.service");
+        assert!(!failed.success);
+        assert_eq!(
+            failed.stderr,
+            "Job for nginx.service failed with result 'failed'."
+        );
+        assert_eq!(subscribed.load(Ordering::SeqCst), 0);
+    }
+}
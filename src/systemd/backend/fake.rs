@@ -0,0 +1,729 @@
+use std::{
+    cell::RefCell,
+    io::Error,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    super::{
+        Journal, JournalQuery, Operation, OperationOutput, OperationType, Priority, Scope, Service,
+        Signal, UnitType,
+    },
+    Backend, Properties, describe,
+};
+
+/// Name, active state, sub state, unit file state, vendor preset and description.
+type UnitEntry = (
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+);
+
+const SYSTEM_UNITS: &[UnitEntry] = &[
+    (
+        "apt-daily.timer",
+        "active",
+        "waiting",
+        "enabled",
+        "enabled",
+        "Daily apt download activities",
+    ),
+    (
+        "cron.service",
+        "active",
+        "running",
+        "enabled",
+        "enabled",
+        "Regular background program processing daemon",
+    ),
+    (
+        "dbus.service",
+        "active",
+        "running",
+        "static",
+        "",
+        "D-Bus System Message Bus",
+    ),
+    (
+        "docker.service",
+        "failed",
+        "failed",
+        "enabled",
+        "enabled",
+        "Docker Application Container Engine",
+    ),
+    (
+        "nginx.service",
+        "inactive",
+        "dead",
+        "disabled",
+        "enabled",
+        "A high performance web server and a reverse proxy server",
+    ),
+    (
+        "postgresql.service",
+        "active",
+        "exited",
+        "enabled",
+        "enabled",
+        "PostgreSQL RDBMS",
+    ),
+    (
+        "multi-user.target",
+        "active",
+        "active",
+        "static",
+        "",
+        "Multi-User System",
+    ),
+    (
+        "ssh.service",
+        "active",
+        "running",
+        "enabled",
+        "enabled",
+        "OpenBSD Secure Shell server",
+    ),
+    (
+        "ssh.socket",
+        "inactive",
+        "dead",
+        "disabled",
+        "enabled",
+        "OpenBSD Secure Shell server socket",
+    ),
+    (
+        "systemd-journald.service",
+        "active",
+        "running",
+        "static",
+        "",
+        "Journal Service",
+    ),
+    (
+        "ufw.service",
+        "inactive",
+        "dead",
+        "masked",
+        "enabled",
+        "Uncomplicated firewall",
+    ),
+];
+
+const USER_UNITS: &[UnitEntry] = &[
+    (
+        "pipewire.service",
+        "active",
+        "running",
+        "enabled",
+        "enabled",
+        "PipeWire Multimedia Service",
+    ),
+    (
+        "syncthing.service",
+        "inactive",
+        "dead",
+        "disabled",
+        "enabled",
+        "Syncthing - Open Source Continuous File Synchronization",
+    ),
+    (
+        "dev-server.service",
+        "failed",
+        "failed",
+        "enabled",
+        "enabled",
+        "Local development server",
+    ),
+];
+
+/// In-memory stand-in for systemd, used to drive the application on machines without it.
+/// Operations change the state of the fake units, so their effect is visible after a reload.
+pub struct Fake {
+    system: RefCell<Vec<Service>>,
+    user: RefCell<Vec<Service>>,
+    /// Seconds since the epoch at which the running fake units pretend to have started at
+    /// the latest.
+    started_at: u64,
+}
+
+fn to_services(entries: &[UnitEntry]) -> Vec<Service> {
+    entries
+        .iter()
+        .map(
+            |(name, active_state, sub_state, unit_file_state, vendor_preset, description)| {
+                Service {
+                    name: (*name).to_string(),
+                    load_state: if *unit_file_state == "masked" {
+                        String::from("masked")
+                    } else {
+                        String::from("loaded")
+                    },
+                    active_state: (*active_state).to_string(),
+                    sub_state: (*sub_state).to_string(),
+                    description: (*description).to_string(),
+                    unit_file_state: (*unit_file_state).to_string(),
+                    vendor_preset: (*vendor_preset).to_string(),
+                    memory: None,
+                    active_since: None,
+                }
+            },
+        )
+        .collect()
+}
+
+impl Default for Fake {
+    fn default() -> Self {
+        Self {
+            system: RefCell::new(to_services(SYSTEM_UNITS)),
+            user: RefCell::new(to_services(USER_UNITS)),
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs().saturating_sub(5400)),
+        }
+    }
+}
+
+impl Fake {
+    fn units(&self, scope: Scope) -> &RefCell<Vec<Service>> {
+        match scope {
+            Scope::System => &self.system,
+            Scope::User => &self.user,
+        }
+    }
+
+    fn failure(reason: String) -> OperationOutput {
+        OperationOutput {
+            success: false,
+            denied: false,
+            stdout: String::new(),
+            stderr: reason,
+        }
+    }
+
+    fn service(&self, scope: Scope, name: &str) -> Result<Service, Error> {
+        self.units(scope)
+            .borrow()
+            .iter()
+            .find(|service| service.name == name)
+            .cloned()
+            .ok_or_else(|| Error::other(format!("Unit {name} not found.")))
+    }
+
+    fn status(&self, scope: Scope, name: &str) -> Result<String, Error> {
+        self.properties(scope, name)
+            .map(|properties| describe(&properties))
+    }
+
+    fn set_active(service: &mut Service, active: bool) {
+        let (active_state, sub_state) = if active {
+            ("active", "running")
+        } else {
+            ("inactive", "dead")
+        };
+
+        service.active_state = String::from(active_state);
+        service.sub_state = String::from(sub_state);
+    }
+
+    fn apply(service: &mut Service, operation_type: OperationType) -> Result<(), String> {
+        let masked = service.unit_file_state == "masked";
+        let active = service.active_state == "active";
+
+        match operation_type {
+            OperationType::Status | OperationType::DaemonReload => {}
+            OperationType::Start
+            | OperationType::Restart
+            | OperationType::ReloadOrRestart
+            | OperationType::Enable
+            | OperationType::EnableNow
+                if masked =>
+            {
+                return Err(format!("Unit {} is masked.", service.name));
+            }
+            OperationType::Start | OperationType::Restart | OperationType::ReloadOrRestart => {
+                Self::set_active(service, true);
+            }
+            OperationType::TryRestart => {
+                if active {
+                    Self::set_active(service, true);
+                }
+            }
+            OperationType::Stop => Self::set_active(service, false),
+            OperationType::Reload => {
+                if !active {
+                    return Err(format!("Unit {} is not active.", service.name));
+                }
+            }
+            OperationType::Enable => service.unit_file_state = String::from("enabled"),
+            OperationType::EnableNow => {
+                service.unit_file_state = String::from("enabled");
+                Self::set_active(service, true);
+            }
+            OperationType::Disable | OperationType::DisableNow => {
+                if service.unit_file_state == "enabled" {
+                    service.unit_file_state = String::from("disabled");
+                }
+
+                if operation_type == OperationType::DisableNow {
+                    Self::set_active(service, false);
+                }
+            }
+            OperationType::Mask => {
+                service.unit_file_state = String::from("masked");
+                service.load_state = String::from("masked");
+            }
+            OperationType::Unmask => {
+                if masked {
+                    service.unit_file_state = String::from("disabled");
+                    service.load_state = String::from("loaded");
+                }
+            }
+            OperationType::Kill(signal) => {
+                if !active {
+                    return Err(format!("Unit {} has no process to kill.", service.name));
+                }
+
+                match signal {
+                    Signal::Term | Signal::Int => Self::set_active(service, false),
+                    Signal::Kill => {
+                        service.active_state = String::from("failed");
+                        service.sub_state = String::from("failed");
+                    }
+                    Signal::Hup | Signal::Usr1 | Signal::Usr2 => {}
+                }
+            }
+            OperationType::ResetFailed => {
+                if service.active_state == "failed" {
+                    Self::set_active(service, false);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Backend for Fake {
+    fn list_units(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        Ok(self
+            .units(scope)
+            .borrow()
+            .iter()
+            .filter(|service| unit_type.matches(&service.name))
+            .map(|service| Service {
+                unit_file_state: String::new(),
+                vendor_preset: String::new(),
+                ..service.clone()
+            })
+            .collect())
+    }
+
+    fn list_unit_files(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        Ok(self
+            .units(scope)
+            .borrow()
+            .iter()
+            .filter(|service| unit_type.matches(&service.name))
+            .map(|service| Service {
+                name: service.name.clone(),
+                unit_file_state: service.unit_file_state.clone(),
+                vendor_preset: service.vendor_preset.clone(),
+                ..Service::default()
+            })
+            .collect())
+    }
+
+    fn may_prompt(&self, _operations: &[Operation]) -> bool {
+        false
+    }
+
+    /// What `systemctl` would be asked, nothing is run.
+    fn command_line(&self, operation: &Operation) -> String {
+        let user = if operation.scope == Scope::User {
+            " --user"
+        } else {
+            ""
+        };
+
+        let args = operation.operation_type.systemctl_args().join(" ");
+
+        if operation.operation_type.is_global() {
+            format!("systemctl{user} {args}")
+        } else {
+            format!("systemctl{user} {args} {}", operation.name)
+        }
+    }
+
+    fn execute(&self, operation: &Operation) -> Result<OperationOutput, Error> {
+        if operation.operation_type == OperationType::Status {
+            return match self.status(operation.scope, &operation.name) {
+                Ok(status) => Ok(OperationOutput {
+                    success: true,
+                    denied: false,
+                    stdout: status,
+                    stderr: String::new(),
+                }),
+                Err(err) => Ok(Self::failure(err.to_string())),
+            };
+        }
+
+        if operation.operation_type.is_global() {
+            return Ok(OperationOutput {
+                success: true,
+                denied: false,
+                stdout: String::new(),
+                stderr: String::new(),
+            });
+        }
+
+        let mut units = self.units(operation.scope).borrow_mut();
+        let Some(service) = units
+            .iter_mut()
+            .find(|service| service.name == operation.name)
+        else {
+            return Ok(Self::failure(format!("Unit {} not found.", operation.name)));
+        };
+
+        Ok(match Self::apply(service, operation.operation_type) {
+            Ok(()) => OperationOutput {
+                success: true,
+                denied: false,
+                stdout: String::new(),
+                stderr: String::new(),
+            },
+            Err(reason) => Self::failure(reason),
+        })
+    }
+
+    fn properties(&self, scope: Scope, name: &str) -> Result<Properties, Error> {
+        let service = self.service(scope, name)?;
+        let running = service.sub_state == "running";
+        // Units with longer names use more memory and started earlier, so that sorting by
+        // either changes the order.
+        let spread = u64::try_from(service.name.len()).unwrap_or_default();
+        let (main_pid, memory, tasks) = if running {
+            ("4242", spread.saturating_mul(1 << 20).to_string(), "3")
+        } else {
+            ("0", String::from("[not set]"), "[not set]")
+        };
+        let active_enter_timestamp = if service.active_state == "active" {
+            format!(
+                "@{}",
+                self.started_at.saturating_sub(spread.saturating_mul(60))
+            )
+        } else {
+            String::new()
+        };
+
+        Ok(Properties::from([
+            (String::from("MainPID"), main_pid.to_string()),
+            (String::from("MemoryCurrent"), memory),
+            (String::from("TasksCurrent"), tasks.to_string()),
+            (String::from("ActiveEnterTimestamp"), active_enter_timestamp),
+            (String::from("Id"), service.name.clone()),
+            (String::from("Description"), service.description.clone()),
+            (String::from("LoadState"), service.load_state.clone()),
+            (String::from("ActiveState"), service.active_state.clone()),
+            (String::from("SubState"), service.sub_state.clone()),
+            (
+                String::from("UnitFileState"),
+                service.unit_file_state.clone(),
+            ),
+            (
+                String::from("UnitFilePreset"),
+                service.vendor_preset.clone(),
+            ),
+        ]))
+    }
+
+    fn journal(&self, scope: Scope, query: &JournalQuery) -> Result<Journal, Error> {
+        let service = self.service(scope, &query.unit)?;
+        // Priority, age in seconds and message of each entry.
+        let mut entries = vec![(
+            Priority::Info,
+            7200,
+            format!("Starting {}...", service.description),
+        )];
+
+        match service.active_state.as_str() {
+            "active" => {
+                entries.push((
+                    Priority::Info,
+                    7199,
+                    format!("Started {}.", service.description),
+                ));
+
+                if service.sub_state == "running" {
+                    for request in 1..=30_u64 {
+                        let age = 3000_u64.saturating_sub(request.saturating_mul(100));
+
+                        entries.push(if request % 10 == 0 {
+                            (
+                                Priority::Warning,
+                                age,
+                                format!("Request #{request} took longer than expected"),
+                            )
+                        } else {
+                            (Priority::Debug, age, format!("Handled request #{request}"))
+                        });
+                    }
+                }
+            }
+            "failed" => {
+                entries.push((
+                    Priority::Error,
+                    7199,
+                    String::from("Main process exited, code=exited, status=1/FAILURE"),
+                ));
+                entries.push((
+                    Priority::Warning,
+                    7199,
+                    String::from("Failed with result 'exit-code'."),
+                ));
+            }
+            _ => entries.push((
+                Priority::Info,
+                7000,
+                format!("Stopped {}.", service.description),
+            )),
+        }
+
+        let prefix = format!("2026-01-01T00:00:00+0000 fake {}[4242]:", service.name);
+        // The cursor of an entry is its position, the journal of a fake unit never grows.
+        let after = query
+            .after_cursor
+            .as_ref()
+            .and_then(|cursor| cursor.parse::<usize>().ok());
+        let mut entries: Vec<(usize, String)> = entries
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, (priority, age, _))| {
+                after.is_none_or(|after| *idx > after)
+                    && query
+                        .priority
+                        .level()
+                        .is_none_or(|level| priority.level().is_some_and(|own| own <= level))
+                    && (after.is_some()
+                        || query.time_range.seconds().is_none_or(|range| *age <= range))
+            })
+            .map(|(idx, (_, _, message))| (idx, format!("{prefix} {message}")))
+            .collect();
+
+        if let Some(lines) = query.lines {
+            let skip = entries.len().saturating_sub(lines);
+            entries.drain(..skip);
+        }
+
+        Ok(Journal {
+            cursor: entries.last().map(|(idx, _)| idx.to_string()),
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::TimeRange;
+    use super::*;
+
+    fn execute(fake: &Fake, operation_type: OperationType, name: &str) -> OperationOutput {
+        fake.execute(&Operation {
+            operation_type,
+            name: name.to_string(),
+            scope: Scope::System,
+        })
+        .unwrap()
+    }
+
+    fn state(fake: &Fake, name: &str) -> (String, String, String) {
+        let service = fake.service(Scope::System, name).unwrap();
+
+        (
+            service.active_state,
+            service.sub_state,
+            service.unit_file_state,
+        )
+    }
+
+    fn strings(
+        active_state: &str,
+        sub_state: &str,
+        unit_file_state: &str,
+    ) -> (String, String, String) {
+        (
+            active_state.to_string(),
+            sub_state.to_string(),
+            unit_file_state.to_string(),
+        )
+    }
+
+    #[test]
+    fn lists_what_systemctl_reports() {
+        let fake = Fake::default();
+        let units = fake.list_units(Scope::System, UnitType::Service).unwrap();
+        let files = fake.list_unit_files(Scope::User, UnitType::All).unwrap();
+
+        assert_eq!(units.len(), 8);
+        assert!(units.iter().all(|unit| unit.unit_file_state.is_empty()));
+        assert_eq!(files.len(), USER_UNITS.len());
+        assert!(files.iter().all(|file| file.active_state.is_empty()));
+        assert!(files.iter().all(|file| !file.unit_file_state.is_empty()));
+    }
+
+    #[test]
+    fn starts_and_stops_units() {
+        let fake = Fake::default();
+
+        assert!(execute(&fake, OperationType::Start, "nginx.service").success);
+        assert_eq!(
+            state(&fake, "nginx.service"),
+            strings("active", "running", "disabled")
+        );
+
+        assert!(execute(&fake, OperationType::Stop, "nginx.service").success);
+        assert_eq!(
+            state(&fake, "nginx.service"),
+            strings("inactive", "dead", "disabled")
+        );
+
+        assert!(!execute(&fake, OperationType::Reload, "nginx.service").success);
+        assert!(execute(&fake, OperationType::TryRestart, "nginx.service").success);
+        assert_eq!(state(&fake, "nginx.service").0, "inactive");
+    }
+
+    #[test]
+    fn enables_disables_and_masks_units() {
+        let fake = Fake::default();
+
+        assert!(execute(&fake, OperationType::EnableNow, "nginx.service").success);
+        assert_eq!(
+            state(&fake, "nginx.service"),
+            strings("active", "running", "enabled")
+        );
+
+        assert!(execute(&fake, OperationType::DisableNow, "nginx.service").success);
+        assert_eq!(
+            state(&fake, "nginx.service"),
+            strings("inactive", "dead", "disabled")
+        );
+
+        assert!(execute(&fake, OperationType::Mask, "nginx.service").success);
+        let output = execute(&fake, OperationType::Start, "nginx.service");
+        assert!(!output.success);
+        assert_eq!(output.stderr, "Unit nginx.service is masked.");
+
+        assert!(execute(&fake, OperationType::Unmask, "nginx.service").success);
+        assert_eq!(state(&fake, "nginx.service").2, "disabled");
+
+        // Static units stay static.
+        assert!(execute(&fake, OperationType::Disable, "dbus.service").success);
+        assert_eq!(state(&fake, "dbus.service").2, "static");
+    }
+
+    #[test]
+    fn kills_and_resets_failed_units() {
+        let fake = Fake::default();
+
+        assert!(execute(&fake, OperationType::Kill(Signal::Hup), "cron.service").success);
+        assert_eq!(state(&fake, "cron.service").0, "active");
+
+        assert!(execute(&fake, OperationType::Kill(Signal::Kill), "cron.service").success);
+        assert_eq!(state(&fake, "cron.service").0, "failed");
+        assert!(!execute(&fake, OperationType::Kill(Signal::Term), "cron.service").success);
+
+        assert!(execute(&fake, OperationType::ResetFailed, "cron.service").success);
+        assert_eq!(
+            state(&fake, "cron.service"),
+            strings("inactive", "dead", "enabled")
+        );
+    }
+
+    #[test]
+    fn fails_on_unknown_units() {
+        let fake = Fake::default();
+        let output = execute(&fake, OperationType::Start, "missing.service");
+
+        assert!(!output.success);
+        assert_eq!(output.stderr, "Unit missing.service not found.");
+        assert!(execute(&fake, OperationType::DaemonReload, "").success);
+        assert!(fake.properties(Scope::System, "missing.service").is_err());
+    }
+
+    #[test]
+    fn fills_in_details() {
+        let fake = Fake::default();
+        let mut units = fake.list_units(Scope::System, UnitType::Service).unwrap();
+
+        fake.fill_details(Scope::System, &mut units).unwrap();
+
+        let unit = |name: &str| units.iter().find(|unit| unit.name == name).unwrap();
+
+        assert_eq!(unit("cron.service").memory, Some(12 << 20));
+        assert_eq!(unit("cron.service").unit_file_state, "enabled");
+        assert_eq!(unit("nginx.service").memory, None);
+        assert_eq!(unit("nginx.service").active_since, None);
+        assert_eq!(unit("nginx.service").vendor_preset, "enabled");
+        assert!(unit("cron.service").active_since > unit("systemd-journald.service").active_since);
+    }
+
+    #[test]
+    fn filters_the_journal() {
+        let fake = Fake::default();
+        let query = |priority, lines| JournalQuery {
+            unit: String::from("docker.service"),
+            lines,
+            priority,
+            time_range: TimeRange::All,
+            after_cursor: None,
+        };
+
+        assert_eq!(
+            fake.journal(Scope::System, &query(Priority::All, None))
+                .unwrap()
+                .entries
+                .len(),
+            3
+        );
+        assert_eq!(
+            fake.journal(Scope::System, &query(Priority::Error, None))
+                .unwrap()
+                .entries
+                .len(),
+            1
+        );
+        assert_eq!(
+            fake.journal(Scope::System, &query(Priority::All, Some(1)))
+                .unwrap()
+                .entries,
+            ["2026-01-01T00:00:00+0000 fake docker.service[4242]: Failed with result 'exit-code'."]
+        );
+    }
+
+    #[test]
+    fn reads_only_the_entries_after_the_cursor() {
+        let fake = Fake::default();
+        let mut query = JournalQuery {
+            unit: String::from("docker.service"),
+            ..JournalQuery::default()
+        };
+        let journal = fake.journal(Scope::System, &query).unwrap();
+
+        assert_eq!(journal.cursor.as_deref(), Some("2"));
+
+        query.after_cursor = Some(String::from("0"));
+        assert_eq!(
+            fake.journal(Scope::System, &query).unwrap().entries,
+            &journal.entries[1..]
+        );
+
+        query.after_cursor = journal.cursor;
+        let journal = fake.journal(Scope::System, &query).unwrap();
+
+        assert!(journal.entries.is_empty());
+        assert_eq!(journal.cursor, None);
+    }
+}
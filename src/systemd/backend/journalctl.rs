@@ -0,0 +1,95 @@
+use std::{io::Error, process::Command};
+
+use super::super::{Journal, JournalQuery, Scope, TimeRange};
+
+/// Replaces the control characters some services log, as they would garble the terminal.
+fn sanitize(line: &str) -> String {
+    line.chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect()
+}
+
+/// Splits the cursor `--show-cursor` prints after the last entry from the entries.
+fn parse(text: &str) -> Journal {
+    let mut entries: Vec<&str> = text.lines().collect();
+    let cursor = entries
+        .last()
+        .and_then(|line| line.strip_prefix("-- cursor: "))
+        .map(str::to_string);
+
+    if cursor.is_some() {
+        entries.pop();
+    }
+
+    Journal {
+        entries: entries.into_iter().map(sanitize).collect(),
+        cursor,
+    }
+}
+
+/// Reads journal entries through `journalctl`, systemd offers no D-Bus API for them.
+pub fn read(scope: Scope, query: &JournalQuery) -> Result<Journal, Error> {
+    let mut command = Command::new("journalctl");
+
+    if scope == Scope::User {
+        command.arg("--user");
+    }
+
+    command.args([
+        "--no-pager",
+        "--quiet",
+        "--show-cursor",
+        "--output=short-iso",
+        "--unit",
+        &query.unit,
+    ]);
+
+    if let Some(lines) = query.lines {
+        command.arg(format!("--lines={lines}"));
+    }
+
+    if let Some(level) = query.priority.level() {
+        command.arg(format!("--priority={level}"));
+    }
+
+    if let Some(cursor) = &query.after_cursor {
+        command.arg(format!("--after-cursor={cursor}"));
+    }
+
+    // journalctl refuses a start time next to a cursor, the entries after it are newer anyway.
+    if query.time_range == TimeRange::Boot {
+        command.arg("--boot");
+    } else if let Some(seconds) = query.time_range.seconds()
+        && query.after_cursor.is_none()
+    {
+        command.arg(format!("--since=-{seconds}s"));
+    }
+
+    let output = command.output()?;
+
+    if !output.status.success() {
+        return Err(Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_cursor_from_the_entries() {
+        let journal = parse("first\nsecond\x1b[0m\n-- cursor: s=1;i=2\n");
+
+        assert_eq!(journal.entries, ["first", "second [0m"]);
+        assert_eq!(journal.cursor.as_deref(), Some("s=1;i=2"));
+
+        let journal = parse("");
+
+        assert!(journal.entries.is_empty());
+        assert_eq!(journal.cursor, None);
+    }
+}
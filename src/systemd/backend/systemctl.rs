@@ -0,0 +1,349 @@
+use std::{
+    collections::HashMap,
+    io::Error,
+    process::{Command, Output, Stdio},
+};
+
+use super::{
+    super::{
+        Escalation, Journal, JournalQuery, Operation, OperationOutput, OperationType, Scope,
+        Service, UnitType, is_root,
+    },
+    Backend, Properties, apply_details, journalctl,
+};
+
+/// Parses the `KEY=value` lines of `systemctl show`.
+fn parse_properties(text: &str) -> Properties {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Parses the output of `systemctl show` for several units, whose properties are separated
+/// by an empty line, keyed by unit name.
+fn parse_units_properties(text: &str) -> HashMap<String, Properties> {
+    text.split("\n\n")
+        .map(parse_properties)
+        .filter_map(|properties| Some((properties.get("Id")?.clone(), properties)))
+        .collect()
+}
+
+/// What `systemctl` printed, or what it reported on stderr when it failed.
+fn stdout(output: &Output) -> Result<String, Error> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        return Err(Error::other(match stderr.trim() {
+            "" => format!("systemctl failed with {}", output.status),
+            stderr => stderr.to_string(),
+        }));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Drives systemd through the `systemctl` command line tool.
+pub struct Systemctl {
+    /// How `systemctl` gets privileges when it needs them.
+    escalation: Escalation,
+    /// Whether privileges are already there, so nothing is escalated or asked for.
+    is_root: bool,
+}
+
+impl Systemctl {
+    pub fn new(escalation: Escalation) -> Self {
+        let is_root = is_root();
+
+        Self {
+            escalation: if is_root {
+                Escalation::None
+            } else {
+                escalation
+            },
+            is_root,
+        }
+    }
+
+    /// `systemctl` targeting the manager of `scope`.
+    fn systemctl(scope: Scope) -> Command {
+        let mut command = Command::new("systemctl");
+
+        if scope == Scope::User {
+            command.arg("--user");
+        }
+
+        command
+    }
+
+    fn list(scope: Scope, unit_type: UnitType, args: &[&str]) -> Result<String, Error> {
+        let mut command = Self::systemctl(scope);
+        command
+            .args(args)
+            .args(["--no-pager", "--no-legend", "--plain"]);
+
+        if unit_type != UnitType::All {
+            command.arg(format!("--type={unit_type}"));
+        }
+
+        stdout(&command.output()?)
+    }
+
+    fn has_cached_credentials(&self) -> bool {
+        let Some(program) = self
+            .escalation
+            .program()
+            .filter(|_| self.escalation.can_check_credentials())
+        else {
+            return false;
+        };
+
+        Command::new(program)
+            .args(["-n", "true"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn command(&self, operation: &Operation) -> Command {
+        let mut command = if operation.operation_type == OperationType::Status {
+            let mut command = Self::systemctl(operation.scope);
+            command.args(["status", "--no-pager"]);
+            command
+        } else if operation.needs_privileges()
+            && let Some(program) = self.escalation.program()
+        {
+            let mut command = Command::new(program);
+            command
+                .arg("systemctl")
+                .args(operation.operation_type.systemctl_args());
+            command
+        } else {
+            let mut command = Self::systemctl(operation.scope);
+            command.args(operation.operation_type.systemctl_args());
+            command
+        };
+
+        if !operation.operation_type.is_global() {
+            command.arg(&operation.name);
+        }
+
+        command
+    }
+
+    fn run(&self, operation: &Operation) -> Result<Output, Error> {
+        self.command(operation).output()
+    }
+}
+
+impl Backend for Systemctl {
+    fn list_units(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        Ok(Self::list(scope, unit_type, &["list-units", "--all"])?
+            .lines()
+            .filter_map(Service::from_list_units_line)
+            .collect())
+    }
+
+    fn list_unit_files(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+        Ok(Self::list(scope, unit_type, &["list-unit-files"])?
+            .lines()
+            .filter_map(Service::from_list_unit_files_line)
+            .collect())
+    }
+
+    /// Without an escalation command `systemctl` asks polkit itself, unless run by root.
+    /// Credentials are checked once, the first prompt of a batch caches them for the rest.
+    fn may_prompt(&self, operations: &[Operation]) -> bool {
+        operations.iter().any(Operation::needs_privileges)
+            && !self.is_root
+            && !self.has_cached_credentials()
+    }
+
+    fn command_line(&self, operation: &Operation) -> String {
+        let command = self.command(operation);
+
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn execute(&self, operation: &Operation) -> Result<OperationOutput, Error> {
+        let output = self.run(operation)?;
+
+        // `systemctl status` exits with 3 for units that are simply not running.
+        let success = output.status.success()
+            || (operation.operation_type == OperationType::Status
+                && output.status.code() == Some(3));
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        let denied = !success
+            && operation.needs_privileges()
+            && self.escalation.is_denied(output.status.code(), &stderr);
+
+        Ok(OperationOutput {
+            success,
+            denied,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr,
+        })
+    }
+
+    fn properties(&self, scope: Scope, name: &str) -> Result<Properties, Error> {
+        let output = Self::systemctl(scope)
+            .args(["show", "--no-pager", "--timestamp=unix", name])
+            .output()?;
+
+        Ok(parse_properties(&stdout(&output)?))
+    }
+
+    /// Asks for the details of all units at once, instead of showing them one by one.
+    fn fill_details(&self, scope: Scope, services: &mut [Service]) -> Result<(), Error> {
+        if services.is_empty() {
+            return Ok(());
+        }
+
+        let output = Self::systemctl(scope)
+            .args([
+                "show",
+                "--no-pager",
+                "--timestamp=unix",
+                "--property=Id,MemoryCurrent,ActiveEnterTimestamp,UnitFileState,UnitFilePreset",
+            ])
+            .args(services.iter().map(|service| &service.name))
+            .output()?;
+
+        let details = parse_units_properties(&stdout(&output)?);
+
+        for service in services {
+            if let Some(properties) = details.get(&service.name) {
+                apply_details(service, properties);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn journal(&self, scope: Scope, query: &JournalQuery) -> Result<Journal, Error> {
+        journalctl::read(scope, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::process::ExitStatusExt, process::ExitStatus};
+
+    use super::*;
+
+    fn systemctl(escalation: Escalation) -> Systemctl {
+        Systemctl {
+            escalation,
+            is_root: false,
+        }
+    }
+
+    fn operation(operation_type: OperationType, scope: Scope) -> Operation {
+        Operation {
+            operation_type,
+            name: String::from("nginx.service"),
+            scope,
+        }
+    }
+
+    #[test]
+    fn parses_properties() {
+        let properties =
+            parse_properties("Id=nginx.service\nExecStart=/usr/bin/nginx -g daemon=on\nnoise\n");
+
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties["Id"], "nginx.service");
+        assert_eq!(properties["ExecStart"], "/usr/bin/nginx -g daemon=on");
+    }
+
+    #[test]
+    fn parses_the_properties_of_several_units() {
+        let units = parse_units_properties(
+            "Id=cron.service\nMemoryCurrent=1024\n\nId=dbus.service\nMemoryCurrent=[not set]\n\nMemoryCurrent=1\n",
+        );
+
+        assert_eq!(units.len(), 2);
+        assert_eq!(units["cron.service"]["MemoryCurrent"], "1024");
+        assert_eq!(units["dbus.service"]["MemoryCurrent"], "[not set]");
+    }
+
+    /// Output of a command that exited with `code`.
+    fn output(code: i32, stdout: &str, stderr: &str) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn reports_the_error_of_a_failed_listing() {
+        assert_eq!(
+            stdout(&output(0, "cron.service loaded active running\n", "")).unwrap(),
+            "cron.service loaded active running\n"
+        );
+        assert_eq!(
+            stdout(&output(
+                1,
+                "",
+                "Failed to connect to bus: No medium found\n"
+            ))
+            .unwrap_err()
+            .to_string(),
+            "Failed to connect to bus: No medium found"
+        );
+        assert_eq!(
+            stdout(&output(1, "partial", "")).unwrap_err().to_string(),
+            "systemctl failed with exit status: 1"
+        );
+    }
+
+    #[test]
+    fn may_prompt_for_batches_with_privileged_operations() {
+        let polkit = systemctl(Escalation::None);
+        let user = || operation(OperationType::Restart, Scope::User);
+
+        assert!(!polkit.may_prompt(&[]));
+        assert!(!polkit.may_prompt(&[user(), operation(OperationType::Status, Scope::System)]));
+        assert!(polkit.may_prompt(&[user(), operation(OperationType::Restart, Scope::System)]));
+    }
+
+    #[test]
+    fn escalates_operations_that_need_privileges() {
+        let sudo = systemctl(Escalation::Sudo);
+
+        assert_eq!(
+            sudo.command_line(&operation(OperationType::Start, Scope::System)),
+            "sudo systemctl start nginx.service"
+        );
+        assert_eq!(
+            sudo.command_line(&operation(OperationType::Start, Scope::User)),
+            "systemctl --user start nginx.service"
+        );
+        assert_eq!(
+            sudo.command_line(&operation(OperationType::Status, Scope::System)),
+            "systemctl status --no-pager nginx.service"
+        );
+        assert_eq!(
+            systemctl(Escalation::None)
+                .command_line(&operation(OperationType::Restart, Scope::System)),
+            "systemctl restart nginx.service"
+        );
+    }
+
+    #[test]
+    fn leaves_the_unit_out_of_global_operations() {
+        assert_eq!(
+            systemctl(Escalation::Doas)
+                .command_line(&operation(OperationType::DaemonReload, Scope::System)),
+            "doas systemctl daemon-reload"
+        );
+    }
+}
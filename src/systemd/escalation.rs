@@ -0,0 +1,144 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    str::FromStr,
+};
+
+/// How `systemctl` is given privileges on the system manager.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum Escalation {
+    #[default]
+    Sudo,
+    Doas,
+    Pkexec,
+    Run0,
+    /// Runs `systemctl` as is, e.g. when already root or to let it ask polkit itself.
+    None,
+}
+
+impl Display for Escalation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sudo => write!(f, "sudo"),
+            Self::Doas => write!(f, "doas"),
+            Self::Pkexec => write!(f, "pkexec"),
+            Self::Run0 => write!(f, "run0"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for Escalation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sudo" => Ok(Self::Sudo),
+            "doas" => Ok(Self::Doas),
+            "pkexec" => Ok(Self::Pkexec),
+            "run0" => Ok(Self::Run0),
+            "none" => Ok(Self::None),
+            _ => Err(format!(
+                "Unknown escalation '{value}', expected one of: sudo, doas, pkexec, run0, none"
+            )),
+        }
+    }
+}
+
+impl Escalation {
+    /// The command `systemctl` runs through, `None` when it runs by itself.
+    pub fn program(self) -> Option<&'static str> {
+        match self {
+            Self::Sudo => Some("sudo"),
+            Self::Doas => Some("doas"),
+            Self::Pkexec => Some("pkexec"),
+            Self::Run0 => Some("run0"),
+            Self::None => None,
+        }
+    }
+
+    /// Only `sudo` and `doas` tell whether they would prompt, with `-n`.
+    pub fn can_check_credentials(self) -> bool {
+        matches!(self, Self::Sudo | Self::Doas)
+    }
+
+    /// Whether a failed command was refused by the escalation, or by polkit, instead of
+    /// failing in `systemctl`. `code` is its exit code and `stderr` what it wrote there.
+    pub fn is_denied(self, code: Option<i32>, stderr: &str) -> bool {
+        let polkit_denied = stderr.lines().any(|line| {
+            line.contains("Access denied") || line.contains("Interactive authentication required")
+        });
+
+        match self {
+            // Their own messages are prefixed with their name, those of systemctl are not.
+            Self::Sudo | Self::Doas => {
+                let prefix = format!("{self}:");
+
+                stderr.lines().any(|line| line.starts_with(&prefix))
+            }
+            // 126 when the authorization was dismissed, 127 when it was refused.
+            Self::Pkexec => matches!(code, Some(126 | 127)) || polkit_denied,
+            Self::Run0 | Self::None => polkit_denied,
+        }
+    }
+}
+
+/// Whether the process runs with an effective user id of 0, from `/proc/self/status`.
+pub fn is_root() -> bool {
+    fs::read_to_string("/proc/self/status").is_ok_and(|status| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Uid:"))
+            .and_then(|ids| ids.split_whitespace().nth(1))
+            == Some("0")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_refusals_of_sudo_and_doas_by_their_prefix() {
+        assert!(Escalation::Sudo.is_denied(Some(1), "sudo: 3 incorrect password attempts\n"));
+        assert!(Escalation::Sudo.is_denied(Some(1), "\nsudo: a password is required\n"));
+        assert!(Escalation::Doas.is_denied(Some(1), "doas: Authentication failed\n"));
+        assert!(!Escalation::Sudo.is_denied(Some(1), "doas: Authentication failed\n"));
+        assert!(!Escalation::Sudo.is_denied(
+            Some(5),
+            "Failed to start nginx.service: Unit nginx.service not found.\n"
+        ));
+    }
+
+    #[test]
+    fn tells_refusals_of_pkexec_by_its_exit_code() {
+        assert!(Escalation::Pkexec.is_denied(Some(126), ""));
+        assert!(Escalation::Pkexec.is_denied(Some(127), ""));
+        assert!(!Escalation::Pkexec.is_denied(Some(1), "Job for nginx.service failed.\n"));
+        assert!(!Escalation::Pkexec.is_denied(None, ""));
+    }
+
+    #[test]
+    fn tells_refusals_of_polkit_by_its_messages() {
+        let denied = "Failed to start nginx.service: Access denied\n";
+        let not_asked = "Failed to start nginx.service: Interactive authentication required.\n";
+
+        for escalation in [Escalation::Pkexec, Escalation::Run0, Escalation::None] {
+            assert!(escalation.is_denied(Some(1), denied));
+            assert!(escalation.is_denied(Some(1), not_asked));
+            assert!(!escalation.is_denied(Some(1), "Unit nginx.service not found.\n"));
+        }
+
+        // Through sudo, systemctl runs as root and polkit is not asked.
+        assert!(!Escalation::Sudo.is_denied(Some(1), denied));
+    }
+
+    #[test]
+    fn checks_credentials_of_sudo_and_doas_only() {
+        assert!(Escalation::Sudo.can_check_credentials());
+        assert!(Escalation::Doas.can_check_credentials());
+        assert!(!Escalation::Pkexec.can_check_credentials());
+        assert!(!Escalation::Run0.can_check_credentials());
+        assert_eq!(Escalation::None.program(), None);
+    }
+}
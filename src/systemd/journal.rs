@@ -0,0 +1,131 @@
+use std::fmt::{self, Display};
+
+/// Minimum importance of the journal entries to read, `All` reads every entry. The more severe
+/// levels are left out, `Error` includes them.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum Priority {
+    #[default]
+    All,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+const PRIORITY_CYCLE: [Priority; 6] = [
+    Priority::All,
+    Priority::Error,
+    Priority::Warning,
+    Priority::Notice,
+    Priority::Info,
+    Priority::Debug,
+];
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Error => write!(f, "err"),
+            Self::Warning => write!(f, "warning"),
+            Self::Notice => write!(f, "notice"),
+            Self::Info => write!(f, "info"),
+            Self::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+impl Priority {
+    pub fn next(self) -> Self {
+        let idx = PRIORITY_CYCLE
+            .iter()
+            .position(|priority| *priority == self)
+            .map_or(0, |idx| idx.saturating_add(1));
+
+        PRIORITY_CYCLE.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Syslog level, as understood by `journalctl --priority`.
+    pub fn level(self) -> Option<u8> {
+        match self {
+            Self::All => None,
+            Self::Error => Some(3),
+            Self::Warning => Some(4),
+            Self::Notice => Some(5),
+            Self::Info => Some(6),
+            Self::Debug => Some(7),
+        }
+    }
+}
+
+/// How far back to read the journal.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum TimeRange {
+    #[default]
+    All,
+    Boot,
+    Hour,
+    Day,
+    Week,
+}
+
+const TIME_RANGE_CYCLE: [TimeRange; 5] = [
+    TimeRange::All,
+    TimeRange::Boot,
+    TimeRange::Hour,
+    TimeRange::Day,
+    TimeRange::Week,
+];
+
+impl Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Boot => write!(f, "this boot"),
+            Self::Hour => write!(f, "last hour"),
+            Self::Day => write!(f, "last day"),
+            Self::Week => write!(f, "last week"),
+        }
+    }
+}
+
+impl TimeRange {
+    pub fn next(self) -> Self {
+        let idx = TIME_RANGE_CYCLE
+            .iter()
+            .position(|range| *range == self)
+            .map_or(0, |idx| idx.saturating_add(1));
+
+        TIME_RANGE_CYCLE.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Length of the range in seconds, `None` for the unbounded ones.
+    pub fn seconds(self) -> Option<u64> {
+        match self {
+            Self::All | Self::Boot => None,
+            Self::Hour => Some(3600),
+            Self::Day => Some(86_400),
+            Self::Week => Some(604_800),
+        }
+    }
+}
+
+/// Which journal entries to read for a unit.
+#[derive(Clone, Default)]
+pub struct JournalQuery {
+    pub unit: String,
+    /// Only the last `lines` entries, all of them when `None`.
+    pub lines: Option<usize>,
+    pub priority: Priority,
+    pub time_range: TimeRange,
+    /// Only the entries after the one at this cursor, the time range is left out then.
+    pub after_cursor: Option<String>,
+}
+
+/// Journal entries read for a query, oldest first.
+#[derive(Default)]
+pub struct Journal {
+    pub entries: Vec<String>,
+    /// Cursor of the last entry, to read only the newer ones next time. `None` without entries.
+    pub cursor: Option<String>,
+}
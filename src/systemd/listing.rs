@@ -0,0 +1,168 @@
+use std::{io::Error, str::FromStr};
+
+use super::{Backend, Scope, Service, UnitType};
+
+/// Whether loaded units or installed unit files are listed.
+#[derive(Default, Eq, PartialEq, Clone, Copy)]
+pub enum Target {
+    #[default]
+    Memory,
+    Files,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "loaded" => Ok(Self::Memory),
+            "files" => Ok(Self::Files),
+            _ => Err(format!(
+                "Unknown target '{value}', expected one of: loaded, files"
+            )),
+        }
+    }
+}
+
+impl Target {
+    /// Names of the fields `Service::columns` returns.
+    pub fn column_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Memory => &["unit", "type", "load", "active", "sub", "description"],
+            Self::Files => &["unit", "type", "state", "preset"],
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Memory => Self::Files,
+            Self::Files => Self::Memory,
+        }
+    }
+}
+
+/// What is listed: the manager, the kind of units and whether loaded units or unit files.
+#[derive(Default, Eq, PartialEq, Clone, Copy)]
+pub struct Listing {
+    pub scope: Scope,
+    pub unit_type: UnitType,
+    pub target: Target,
+}
+
+const COLUMN_SEPARATOR: &str = "  ";
+
+impl Listing {
+    /// Lays the services out in aligned columns, one line per service.
+    pub fn layout(services: &[Service], target: Target) -> Vec<String> {
+        let rows: Vec<Vec<&str>> = services
+            .iter()
+            .map(|service| service.columns(target))
+            .collect();
+
+        Self::align(&rows)
+    }
+
+    /// Lays rows of columns out aligned, one line per row.
+    pub fn align(rows: &[Vec<&str>]) -> Vec<String> {
+        let mut widths: Vec<usize> = Vec::new();
+
+        for columns in rows {
+            for (idx, column) in columns.iter().enumerate() {
+                if let Some(width) = widths.get_mut(idx) {
+                    *width = (*width).max(column.len());
+                } else {
+                    widths.push(column.len());
+                }
+            }
+        }
+
+        rows.iter()
+            .map(|columns| {
+                let last_idx = columns.len().saturating_sub(1);
+                let mut line = String::new();
+
+                for (idx, column) in columns.iter().enumerate() {
+                    line.push_str(column);
+
+                    if idx != last_idx {
+                        let width = widths.get(idx).copied().unwrap_or_default();
+                        line.push_str(&" ".repeat(width.saturating_sub(column.len())));
+                        line.push_str(COLUMN_SEPARATOR);
+                    }
+                }
+
+                line.truncate(line.trim_end().len());
+                line
+            })
+            .collect()
+    }
+
+    pub fn load(self, backend: &dyn Backend) -> Result<Vec<Service>, Error> {
+        if self.target == Target::Memory {
+            backend.list_units(self.scope, self.unit_type)
+        } else {
+            backend.list_unit_files(self.scope, self.unit_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, active_state: &str, description: &str) -> Service {
+        Service {
+            name: name.to_string(),
+            load_state: String::from("loaded"),
+            active_state: active_state.to_string(),
+            sub_state: String::from("dead"),
+            description: description.to_string(),
+            unit_file_state: String::from("enabled"),
+            ..Service::default()
+        }
+    }
+
+    #[test]
+    fn aligns_the_columns() {
+        let services = [
+            service("ssh.service", "active", "OpenBSD Secure Shell server"),
+            service("a.timer", "inactive", ""),
+        ];
+
+        assert_eq!(
+            Listing::layout(&services, Target::Memory),
+            [
+                "ssh.service  service  loaded  active    dead  OpenBSD Secure Shell server",
+                "a.timer      timer    loaded  inactive  dead",
+            ]
+        );
+        assert_eq!(
+            Listing::layout(&services, Target::Files),
+            [
+                "ssh.service  service  enabled",
+                "a.timer      timer    enabled"
+            ]
+        );
+    }
+
+    #[test]
+    fn aligns_rows_of_different_lengths() {
+        assert_eq!(
+            Listing::align(&[vec!["a", "long", "x"], vec!["bbb", "c"]]),
+            ["a    long  x", "bbb  c"]
+        );
+        assert!(Listing::align(&[]).is_empty());
+    }
+
+    #[test]
+    fn parses_and_toggles_targets() {
+        assert!(Target::from_str("loaded") == Ok(Target::Memory));
+        assert!(Target::from_str("files") == Ok(Target::Files));
+        assert!(Target::from_str("all").is_err());
+        assert!(Target::Memory.toggle() == Target::Files);
+        assert_eq!(
+            Target::Files.column_names().len(),
+            service("a.service", "", "").columns(Target::Files).len()
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    fmt::{self, Display},
+    mem::discriminant,
+    str::FromStr,
+};
+
+use super::{Scope, Signal};
+
+// clippy::struct_field_names: the operation type is what the other fields are about
+#[allow(clippy::struct_field_names)]
+pub struct Operation {
+    pub operation_type: OperationType,
+    pub name: String,
+    pub scope: Scope,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OperationType {
+    Status,
+    Start,
+    Stop,
+    Reload,
+    Restart,
+    Enable,
+    Disable,
+    /// Enables and starts, `systemctl enable --now`.
+    EnableNow,
+    /// Disables and stops, `systemctl disable --now`.
+    DisableNow,
+    ReloadOrRestart,
+    TryRestart,
+    Mask,
+    Unmask,
+    Kill(Signal),
+    ResetFailed,
+    /// Reloads the configuration of the manager, not of a unit.
+    DaemonReload,
+}
+
+impl Display for OperationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status => write!(f, "status"),
+            Self::Start => write!(f, "start"),
+            Self::Stop => write!(f, "stop"),
+            Self::Reload => write!(f, "reload"),
+            Self::Restart => write!(f, "restart"),
+            Self::Enable => write!(f, "enable"),
+            Self::Disable => write!(f, "disable"),
+            Self::EnableNow => write!(f, "enable-now"),
+            Self::DisableNow => write!(f, "disable-now"),
+            Self::ReloadOrRestart => write!(f, "reload-or-restart"),
+            Self::TryRestart => write!(f, "try-restart"),
+            Self::Mask => write!(f, "mask"),
+            Self::Unmask => write!(f, "unmask"),
+            Self::Kill(_) => write!(f, "kill"),
+            Self::ResetFailed => write!(f, "reset-failed"),
+            Self::DaemonReload => write!(f, "daemon-reload"),
+        }
+    }
+}
+
+impl FromStr for OperationType {
+    type Err = String;
+
+    /// Underscores stand for hyphens, as in the action names of the keymap.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.replace('_', "-");
+
+        OperationType::ALL
+            .into_iter()
+            .find(|operation_type| operation_type.to_string() == name)
+            .ok_or_else(|| format!("Unknown operation '{value}'"))
+    }
+}
+
+impl OperationType {
+    pub const ALL: [Self; 16] = [
+        Self::Status,
+        Self::Start,
+        Self::Stop,
+        Self::Reload,
+        Self::Restart,
+        Self::Enable,
+        Self::Disable,
+        Self::EnableNow,
+        Self::DisableNow,
+        Self::ReloadOrRestart,
+        Self::TryRestart,
+        Self::Mask,
+        Self::Unmask,
+        Self::Kill(Signal::Term),
+        Self::ResetFailed,
+        Self::DaemonReload,
+    ];
+
+    /// Arguments of `systemctl` for the operation, the unit name goes after them.
+    pub fn systemctl_args(self) -> Vec<String> {
+        match self {
+            Self::EnableNow => vec![String::from("enable"), String::from("--now")],
+            Self::DisableNow => vec![String::from("disable"), String::from("--now")],
+            Self::Kill(signal) => vec![String::from("kill"), format!("--signal={signal}")],
+            operation_type => vec![operation_type.to_string()],
+        }
+    }
+
+    /// Whether the operation acts on the manager itself instead of on units.
+    pub fn is_global(self) -> bool {
+        self == Self::DaemonReload
+    }
+
+    /// Whether both are the same operation, whatever signal they send.
+    pub fn is_same(self, other: Self) -> bool {
+        discriminant(&self) == discriminant(&other)
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation_type {
+            OperationType::Kill(signal) => write!(f, "kill {} with {signal}", self.name),
+            operation_type if operation_type.is_global() => write!(f, "{operation_type}"),
+            operation_type => write!(f, "{operation_type} {}", self.name),
+        }
+    }
+}
+
+/// Outcome of an executed operation.
+pub struct OperationOutput {
+    pub success: bool,
+    /// The operation was refused before it ran, e.g. on a wrong password.
+    pub denied: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl OperationOutput {
+    /// One line summary of the outcome, suitable for the message bar.
+    pub fn summary(&self, operation: &Operation) -> String {
+        if self.success {
+            return format!("{operation}: done");
+        }
+
+        let mut lines = self.stderr.lines().filter(|line| !line.trim().is_empty());
+
+        // Escalation commands give up with their last line, after e.g. "Sorry, try again".
+        let (outcome, reason) = if self.denied {
+            ("was not authorized", lines.next_back())
+        } else {
+            ("failed", lines.next())
+        };
+
+        match reason {
+            Some(reason) => format!("{operation} {outcome}: {}", reason.trim()),
+            None => format!("{operation} {outcome}"),
+        }
+    }
+}
+
+impl Operation {
+    pub fn new(operation_type: OperationType, name: String, scope: Scope) -> Self {
+        Self {
+            operation_type,
+            name,
+            scope,
+        }
+    }
+
+    /// The operation on each of `names`, or once on the manager when it is a global one.
+    pub fn for_units(operation_type: OperationType, names: Vec<String>, scope: Scope) -> Vec<Self> {
+        if operation_type.is_global() {
+            return vec![Self::new(operation_type, String::new(), scope)];
+        }
+
+        names
+            .into_iter()
+            .map(|name| Self::new(operation_type, name, scope))
+            .collect()
+    }
+
+    /// Whether the operation changes the state of the system manager and therefore needs privileges.
+    /// The user manager belongs to the user, so nothing done there needs them.
+    pub fn needs_privileges(&self) -> bool {
+        self.scope == Scope::System && self.operation_type != OperationType::Status
+    }
+}
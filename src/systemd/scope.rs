@@ -0,0 +1,44 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Which service manager units are listed from and operated on.
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub enum Scope {
+    #[default]
+    System,
+    User,
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System => write!(f, "system"),
+            Self::User => write!(f, "user"),
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "system" => Ok(Self::System),
+            "user" => Ok(Self::User),
+            _ => Err(format!(
+                "Unknown scope '{value}', expected one of: system, user"
+            )),
+        }
+    }
+}
+
+impl Scope {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::System => Self::User,
+            Self::User => Self::System,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use super::Target;
+
+/// A unit as reported by systemd.
+/// Fields that are not part of the listing the unit came from are left empty:
+/// `list-units` does not report file states and `list-unit-files` does not report runtime states.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Service {
+    pub name: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub description: String,
+    pub unit_file_state: String,
+    pub vendor_preset: String,
+    /// Bytes of memory in use, only known after `Backend::fill_details`.
+    pub memory: Option<u64>,
+    /// Seconds since the epoch at which the unit became active, only known after
+    /// `Backend::fill_details`.
+    pub active_since: Option<u64>,
+}
+
+/// Splits `line` into `count` whitespace separated columns followed by the untouched remainder.
+fn split_columns(line: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+    let mut rest = line.trim_start();
+    let mut columns = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (column, remainder) = rest.split_at(end);
+
+        columns.push(column);
+        rest = remainder.trim_start();
+    }
+
+    Some((columns, rest.trim_end()))
+}
+
+/// Failed or missing units are prefixed with a bullet, which is not part of the unit name.
+fn strip_bullet(line: &str) -> &str {
+    let line = line.trim_start();
+
+    line.strip_prefix('●')
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line)
+}
+
+impl Service {
+    /// Fields shown when listing loaded units or unit files.
+    pub fn columns(&self, target: Target) -> Vec<&str> {
+        if target == Target::Memory {
+            vec![
+                &self.name,
+                self.unit_type(),
+                &self.load_state,
+                &self.active_state,
+                &self.sub_state,
+                &self.description,
+            ]
+        } else {
+            vec![
+                &self.name,
+                self.unit_type(),
+                &self.unit_file_state,
+                &self.vendor_preset,
+            ]
+        }
+    }
+
+    /// Type of the unit, taken from the suffix of its name.
+    pub fn unit_type(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(_, suffix)| suffix)
+    }
+
+    /// Parses a line of `systemctl list-units --plain --no-legend`:
+    /// `UNIT LOAD ACTIVE SUB DESCRIPTION`
+    pub fn from_list_units_line(line: &str) -> Option<Self> {
+        let (columns, description) = split_columns(strip_bullet(line), 4)?;
+        let [name, load_state, active_state, sub_state] = columns.as_slice() else {
+            return None;
+        };
+
+        Some(Self {
+            name: (*name).to_string(),
+            load_state: (*load_state).to_string(),
+            active_state: (*active_state).to_string(),
+            sub_state: (*sub_state).to_string(),
+            description: description.to_string(),
+            ..Self::default()
+        })
+    }
+
+    /// Parses a line of `systemctl list-unit-files --plain --no-legend`:
+    /// `UNIT FILE STATE [PRESET]`. Older systemd versions do not print the preset.
+    pub fn from_list_unit_files_line(line: &str) -> Option<Self> {
+        let (columns, vendor_preset) = split_columns(line, 2)?;
+        let [name, unit_file_state] = columns.as_slice() else {
+            return None;
+        };
+
+        Some(Self {
+            name: (*name).to_string(),
+            unit_file_state: (*unit_file_state).to_string(),
+            vendor_preset: vendor_preset.to_string(),
+            ..Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_units_lines() {
+        let service = Service::from_list_units_line(
+            "  nginx.service  loaded active running A high performance web server  ",
+        )
+        .unwrap();
+
+        assert_eq!(service.name, "nginx.service");
+        assert_eq!(service.load_state, "loaded");
+        assert_eq!(service.active_state, "active");
+        assert_eq!(service.sub_state, "running");
+        assert_eq!(service.description, "A high performance web server");
+        assert!(service.unit_file_state.is_empty());
+    }
+
+    #[test]
+    fn strips_the_bullet_of_failed_units() {
+        for line in [
+            "● docker.service loaded failed failed Docker Application Container Engine",
+            "* docker.service loaded failed failed Docker Application Container Engine",
+        ] {
+            let service = Service::from_list_units_line(line).unwrap();
+
+            assert_eq!(service.name, "docker.service");
+            assert_eq!(service.active_state, "failed");
+        }
+    }
+
+    #[test]
+    fn keeps_empty_descriptions_and_rejects_short_lines() {
+        let service = Service::from_list_units_line("a.mount loaded active mounted").unwrap();
+
+        assert_eq!(service.sub_state, "mounted");
+        assert!(service.description.is_empty());
+        assert!(Service::from_list_units_line("a.mount loaded active").is_none());
+        assert!(Service::from_list_units_line("").is_none());
+    }
+
+    #[test]
+    fn parses_list_unit_files_lines() {
+        let with_preset =
+            Service::from_list_unit_files_line("ssh.service enabled enabled").unwrap();
+        let without_preset = Service::from_list_unit_files_line("ssh.service static").unwrap();
+
+        assert_eq!(with_preset.name, "ssh.service");
+        assert_eq!(with_preset.unit_file_state, "enabled");
+        assert_eq!(with_preset.vendor_preset, "enabled");
+        assert!(with_preset.active_state.is_empty());
+        assert!(without_preset.vendor_preset.is_empty());
+        assert!(Service::from_list_unit_files_line("ssh.service").is_none());
+    }
+
+    #[test]
+    fn takes_the_unit_type_from_the_name() {
+        let service = |name: &str| Service {
+            name: name.to_string(),
+            ..Service::default()
+        };
+
+        assert_eq!(service("ssh.socket").unit_type(), "socket");
+        assert_eq!(service("dev-sda1.device").unit_type(), "device");
+        assert_eq!(service("nameless").unit_type(), "");
+    }
+}
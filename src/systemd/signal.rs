@@ -0,0 +1,72 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Signals that can be sent to the processes of a unit.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Signal {
+    #[default]
+    Term,
+    Kill,
+    Hup,
+    Int,
+    Usr1,
+    Usr2,
+}
+
+impl Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Term => write!(f, "SIGTERM"),
+            Self::Kill => write!(f, "SIGKILL"),
+            Self::Hup => write!(f, "SIGHUP"),
+            Self::Int => write!(f, "SIGINT"),
+            Self::Usr1 => write!(f, "SIGUSR1"),
+            Self::Usr2 => write!(f, "SIGUSR2"),
+        }
+    }
+}
+
+impl FromStr for Signal {
+    type Err = String;
+
+    /// Accepts the names with or without `SIG`, in any case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.to_ascii_uppercase();
+        let name = name.strip_prefix("SIG").unwrap_or(&name);
+
+        Self::ALL
+            .into_iter()
+            .find(|signal| signal.to_string().strip_prefix("SIG") == Some(name))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown signal '{value}', expected one of: TERM, KILL, HUP, INT, USR1, USR2"
+                )
+            })
+    }
+}
+
+impl Signal {
+    pub const ALL: [Self; 6] = [
+        Self::Term,
+        Self::Kill,
+        Self::Hup,
+        Self::Int,
+        Self::Usr1,
+        Self::Usr2,
+    ];
+
+    /// The signal number on Linux.
+    #[cfg_attr(not(feature = "dbus"), allow(dead_code))]
+    pub fn number(self) -> i32 {
+        match self {
+            Self::Term => 15,
+            Self::Kill => 9,
+            Self::Hup => 1,
+            Self::Int => 2,
+            Self::Usr1 => 10,
+            Self::Usr2 => 12,
+        }
+    }
+}
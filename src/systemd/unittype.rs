@@ -0,0 +1,93 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Kind of units to list, `All` lists every kind at once.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum UnitType {
+    All,
+    #[default]
+    Service,
+    Socket,
+    Target,
+    Timer,
+    Path,
+    Mount,
+    Automount,
+    Swap,
+    Slice,
+    Scope,
+    Device,
+}
+
+const CYCLE: [UnitType; 12] = [
+    UnitType::Service,
+    UnitType::Socket,
+    UnitType::Target,
+    UnitType::Timer,
+    UnitType::Path,
+    UnitType::Mount,
+    UnitType::Automount,
+    UnitType::Swap,
+    UnitType::Slice,
+    UnitType::Scope,
+    UnitType::Device,
+    UnitType::All,
+];
+
+impl Display for UnitType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Service => write!(f, "service"),
+            Self::Socket => write!(f, "socket"),
+            Self::Target => write!(f, "target"),
+            Self::Timer => write!(f, "timer"),
+            Self::Path => write!(f, "path"),
+            Self::Mount => write!(f, "mount"),
+            Self::Automount => write!(f, "automount"),
+            Self::Swap => write!(f, "swap"),
+            Self::Slice => write!(f, "slice"),
+            Self::Scope => write!(f, "scope"),
+            Self::Device => write!(f, "device"),
+        }
+    }
+}
+
+impl FromStr for UnitType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        CYCLE
+            .into_iter()
+            .find(|unit_type| unit_type.to_string() == value)
+            .ok_or_else(|| {
+                let names: Vec<String> = CYCLE.iter().map(ToString::to_string).collect();
+
+                format!(
+                    "Unknown unit type '{value}', expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl UnitType {
+    pub fn next(self) -> Self {
+        let idx = CYCLE
+            .iter()
+            .position(|unit_type| *unit_type == self)
+            .map_or(0, |idx| idx.saturating_add(1));
+
+        CYCLE.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Whether a unit called `name` is of this type.
+    pub fn matches(self, name: &str) -> bool {
+        self == Self::All
+            || name
+                .rsplit_once('.')
+                .is_some_and(|(_, suffix)| suffix == self.to_string())
+    }
+}
@@ -1,20 +1,33 @@
-use crossterm::event::{Event, KeyEvent, KeyEventKind, read};
+use crossterm::event::{Event, KeyEvent, KeyEventKind, poll, read};
 use std::{
+    cmp::min,
     fmt::Display,
-    io::Error,
+    io::{Error, stdin},
     panic::{set_hook, take_hook},
+    time::{Duration, Instant},
 };
 
 mod annotatedstring;
 mod annotation;
 mod annotationtype;
 mod command;
-mod operation;
+mod filtermode;
+mod filterquery;
+mod format;
+mod keymap;
+mod quickfilter;
+mod searchpattern;
+mod sortorder;
 mod terminal;
+mod theme;
 mod tuistatus;
 mod uicomponents;
 
-use crate::prelude::*;
+use crate::{
+    config::Config,
+    prelude::*,
+    systemd::{Backend, JournalQuery, Listing, Operation, OperationOutput, OperationType, Signal},
+};
 use annotatedstring::AnnotatedString;
 use annotation::Annotation;
 use annotationtype::AnnotationType;
@@ -22,19 +35,99 @@ use command::{
     Command::{self, Edit, Move, System},
     Edit::{Insert, InsertNewLine},
     Move::{Down, Up},
-    System::{Dismiss, Quit, Resize},
+    System::{Dismiss, Quit, Resize, ToggleCase, ToggleRegex, ToggleWholeWord},
 };
-use operation::{Operation, OperationType};
+pub use filtermode::FilterMode;
+pub use filterquery::FilterQuery;
+use keymap::Action;
+pub use keymap::Keymap;
+pub use quickfilter::QuickFilter;
+pub use searchpattern::SearchOptions;
+use searchpattern::SearchPattern;
+pub use sortorder::SortOrder;
 use terminal::Terminal;
+use theme::ColorDepth;
+pub use theme::{Theme, parse_color, parse_effect};
 use tuistatus::TuiStatus;
-use uicomponents::{FilterBar, MessageBar, SearchBar, StatusBar, UIComponent, View};
+use uicomponents::{
+    ConfirmDialog, DetailPane, FilterBar, LogView, MessageBar, SearchBar, Searchable, StatusBar,
+    UIComponent, View,
+};
 
-#[derive(Default, Eq, PartialEq, Clone, Copy)]
-pub enum Target {
-    #[default]
-    Memory,
-    Files,
-}
+/// Rows of the detail pane at most, including its title.
+const MAX_DETAILS_HEIGHT: usize = 12;
+/// How often the journal is read again in follow mode.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+/// Actions shown on the left of the default message.
+const LISTING_ACTIONS: [Action; 3] = [
+    Action::ToggleTarget,
+    Action::ToggleScope,
+    Action::NextUnitType,
+];
+/// Actions shown by the help action.
+const HELP_ACTIONS: [Action; 8] = [
+    Action::Filter,
+    Action::Search,
+    Action::SearchNext,
+    Action::SearchPrev,
+    Action::Mark,
+    Action::MarkRange,
+    Action::MarkAll,
+    Action::Refresh,
+];
+/// Actions shown by the show keys action, on its first page.
+const UNIT_ACTIONS: [Action; 9] = [
+    Action::Details,
+    Action::Logs,
+    Action::Operation(OperationType::Status),
+    Action::Operation(OperationType::Start),
+    Action::Operation(OperationType::Stop),
+    Action::Operation(OperationType::Reload),
+    Action::Operation(OperationType::Restart),
+    Action::Operation(OperationType::Enable),
+    Action::Operation(OperationType::Disable),
+];
+/// Actions shown on the second page of the show keys action.
+const MORE_UNIT_ACTIONS: [Action; 9] = [
+    Action::Operation(OperationType::EnableNow),
+    Action::Operation(OperationType::DisableNow),
+    Action::Operation(OperationType::ReloadOrRestart),
+    Action::Operation(OperationType::TryRestart),
+    Action::Operation(OperationType::Mask),
+    Action::Operation(OperationType::Unmask),
+    Action::Operation(OperationType::Kill(Signal::Term)),
+    Action::Operation(OperationType::ResetFailed),
+    Action::Operation(OperationType::DaemonReload),
+];
+/// Actions shown on the last page of the show keys action.
+const FILTER_SORT_ACTIONS: [Action; 6] = [
+    Action::QuickFilter(QuickFilter::Failed),
+    Action::QuickFilter(QuickFilter::Running),
+    Action::QuickFilter(QuickFilter::EnabledInactive),
+    Action::QuickFilter(QuickFilter::Masked),
+    Action::Sort,
+    Action::ReverseSort,
+];
+/// Keys choosing the signal kill sends.
+const SIGNAL_KEYS: [(char, Signal); 6] = [
+    ('t', Signal::Term),
+    ('k', Signal::Kill),
+    ('h', Signal::Hup),
+    ('i', Signal::Int),
+    ('1', Signal::Usr1),
+    ('2', Signal::Usr2),
+];
+/// Actions shown by the help action of the log view.
+const LOG_ACTIONS: [Action; 8] = [
+    Action::Follow,
+    Action::Priority,
+    Action::TimeRange,
+    Action::Top,
+    Action::Bottom,
+    Action::Search,
+    Action::SearchNext,
+    Action::SearchPrev,
+];
 
 #[derive(Eq, PartialEq, Default, Clone, Copy)]
 pub enum Mode {
@@ -42,21 +135,33 @@ pub enum Mode {
     Search,
     #[default]
     Normal,
+    Log,
+    LogSearch,
+    Confirm,
+    /// Choosing the signal to kill the units with.
+    Signal,
 }
 
 impl Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Filter => write!(f, "FILTER"),
-            Self::Search => write!(f, "SEARCH"),
+            Self::Search | Self::LogSearch => write!(f, "SEARCH"),
             Self::Normal => write!(f, "NORMAL"),
+            Self::Log => write!(f, "LOG"),
+            Self::Confirm => write!(f, "CONFIRM"),
+            Self::Signal => write!(f, "SIGNAL"),
         }
     }
 }
 
 impl Mode {
     fn is_search(mode: Self) -> bool {
-        mode == Self::Search
+        matches!(mode, Self::Search | Self::LogSearch)
+    }
+
+    fn is_log(mode: Self) -> bool {
+        matches!(mode, Self::Log | Self::LogSearch)
     }
 
     fn is_filter(mode: Self) -> bool {
@@ -64,7 +169,6 @@ impl Mode {
     }
 }
 
-#[derive(Default)]
 pub struct Tui {
     should_quit: bool,
     mode: Mode,
@@ -74,33 +178,66 @@ pub struct Tui {
     filter_bar: FilterBar,
     search_bar: SearchBar,
     message_bar: MessageBar,
-    operation: Option<Operation>,
+    detail_pane: DetailPane,
+    show_details: bool,
+    log_view: LogView,
+    confirm_dialog: ConfirmDialog,
+    /// Operations that ask before they run.
+    confirm: Vec<OperationType>,
+    /// Operations waiting for the answer of the confirm dialog.
+    pending: Vec<Operation>,
+    /// Page of the show keys action shown next.
+    keys_page: usize,
+    refresh_interval: Option<Duration>,
+    last_refresh: Instant,
+    picker: bool,
+    picked: Option<Vec<String>>,
     multiplier: Option<String>,
-    target: Target,
+    listing: Listing,
+    /// Units shown on top of the text filter, all when `None`.
+    quick_filter: Option<QuickFilter>,
+    sort: SortOrder,
+    keymap: Keymap,
+    backend: Box<dyn Backend>,
 }
 
 impl Drop for Tui {
     fn drop(&mut self) {
         let _ = Terminal::terminate();
+    }
+}
 
-        if self.should_quit
-            && let Some(operation) = &self.operation
+impl Tui {
+    fn switch_listing(&mut self, listing: Listing) {
+        match self
+            .view
+            .load(self.backend.as_ref(), listing, self.quick_filter, self.sort)
         {
-            operation.execute();
+            Ok(()) => {
+                self.listing = listing;
+                self.filter_bar.clear_value();
+                self.filter_view();
+                self.view.scroll_to_start();
+                self.detail_pane.invalidate();
+            }
+            Err(err) => self.message_bar.update_message(&format!(
+                "Could not load {} units of the {} manager: {err}",
+                listing.unit_type, listing.scope
+            )),
         }
     }
-}
 
-impl Tui {
-    fn toggle_target(&mut self) -> Result<(), Error> {
-        self.target = match self.target {
-            Target::Memory => Target::Files,
-            Target::Files => Target::Memory,
-        };
+    fn process_listing_action(&mut self, action: Action) {
+        let mut listing = self.listing;
 
-        self.view.load(self.target)?;
+        match action {
+            Action::ToggleTarget => listing.target = listing.target.toggle(),
+            Action::ToggleScope => listing.scope = listing.scope.toggle(),
+            Action::NextUnitType => listing.unit_type = listing.unit_type.next(),
+            _ => return,
+        }
 
-        Ok(())
+        self.switch_listing(listing);
     }
 
     fn append_multiplier(&mut self, digit: char) {
@@ -119,27 +256,392 @@ impl Tui {
         }
     }
 
-    fn set_operation_result(&mut self, operation_type: OperationType) {
-        let name = self.view.get_selected_service_name();
-        if let Some(name) = name {
-            self.operation = Some(Operation::new(operation_type, name));
+    fn process_operation_action(&mut self, operation_type: OperationType) {
+        if self.picker {
+            self.message_bar
+                .update_message("Operations are turned off while picking units");
+            return;
+        }
+
+        if let OperationType::Kill(_) = operation_type {
+            let signals: Vec<String> = SIGNAL_KEYS
+                .iter()
+                .map(|(key, signal)| format!("{signal}: {key}"))
+                .collect();
+
+            self.mode = Mode::Signal;
+            self.message_bar
+                .update_message(&format!("{} | cancel: any other key", signals.join(" | ")));
+            return;
+        }
+
+        self.run_operation(operation_type);
+    }
+
+    fn process_mark_action(&mut self, action: Action) {
+        match action {
+            Action::Mark => self.view.toggle_mark(),
+            Action::MarkRange => {
+                if self.view.toggle_mark_range() {
+                    self.message_bar.update_message(&format!(
+                        "Move to the end of the range and press {} again",
+                        self.keymap.keys(Mode::Normal, Action::MarkRange)
+                    ));
+                } else {
+                    self.message_bar.clear_message();
+                }
+            }
+            Action::MarkAll => self.view.mark_all(),
+            _ => {}
+        }
+    }
+
+    /// Help messages are generated from the keymap, so they show the keys actually bound.
+    fn help_message(&self, actions: &[Action]) -> String {
+        self.keymap.help(Mode::Normal, actions)
+    }
+
+    fn log_help_message(&self) -> String {
+        let close = self.keymap.keys(Mode::Log, Action::Close);
+        let back = if close.is_empty() {
+            String::from("esc")
+        } else {
+            format!("esc/{close}")
+        };
+
+        format!(
+            "{} | back: {back}",
+            self.keymap.help(Mode::Log, &LOG_ACTIONS)
+        )
+    }
+
+    /// Runs the operation on the marked services, or on the selected one if none is marked,
+    /// after asking when the operation is one to confirm. Global operations ignore both.
+    fn run_operation(&mut self, operation_type: OperationType) {
+        let mut names = self.view.get_marked_service_names();
+
+        if names.is_empty()
+            && let Some(name) = self.view.get_selected_service_name()
+        {
+            names.push(name);
+        }
+
+        let operations = Operation::for_units(operation_type, names, self.listing.scope);
+
+        if operations.is_empty() {
+            return;
+        }
+
+        if self
+            .confirm
+            .iter()
+            .any(|confirm| confirm.is_same(operation_type))
+        {
+            self.ask_confirmation(operations);
+        } else {
+            self.execute_operations(&operations);
+        }
+    }
+
+    fn ask_confirmation(&mut self, operations: Vec<Operation>) {
+        let title = match operations.as_slice() {
+            [operation] => operation.to_string(),
+            _ => format!(
+                "{} {} units",
+                operations
+                    .first()
+                    .map(|operation| operation.operation_type.to_string())
+                    .unwrap_or_default(),
+                operations.len()
+            ),
+        };
+        let commands = operations
+            .iter()
+            .map(|operation| self.backend.command_line(operation))
+            .collect();
+
+        self.confirm_dialog.open(title, commands);
+        self.pending = operations;
+        self.mode = Mode::Confirm;
+        self.message_bar
+            .update_message("run: y | cancel: any other key");
+    }
+
+    fn execute_operations(&mut self, operations: &[Operation]) {
+        match operations {
+            [] => return,
+            [operation] => self.execute_operation(operation),
+            _ => self.execute_batch(operations),
+        }
+
+        self.view.expire_details();
+        self.refresh_list();
+    }
+
+    /// Loads the services again, keeping the filter, the search and the selected service.
+    fn refresh_list(&mut self) {
+        self.last_refresh = Instant::now();
+
+        match self.view.reload(
+            self.backend.as_ref(),
+            self.listing,
+            self.filter_bar.query(),
+            self.filter_bar.mode(),
+            self.quick_filter,
+            self.sort,
+        ) {
+            Ok(()) => self.detail_pane.invalidate(),
+            Err(err) => self
+                .message_bar
+                .update_message(&format!("Could not reload services: {err}")),
+        }
+    }
+
+    fn execute_operation(&mut self, operation: &Operation) {
+        let is_status = operation.operation_type == OperationType::Status;
+        let needs_terminal = is_status || self.backend.may_prompt(std::slice::from_ref(operation));
+
+        if needs_terminal {
+            let _ = Terminal::suspend();
+        }
+
+        let result = self.backend.execute(operation);
+
+        if is_status && let Ok(output) = &result {
+            let _ = Self::show_output(output);
+        }
+
+        if needs_terminal {
+            let _ = Terminal::resume();
+            self.handle_resize_command(self.terminal_size);
+        }
+
+        match result {
+            Ok(output) => self.message_bar.update_message(&output.summary(operation)),
+            Err(err) => self
+                .message_bar
+                .update_message(&format!("{operation} could not be executed: {err}")),
+        }
+    }
+
+    /// Executes every operation on the suspended terminal, reporting the outcome of each one.
+    fn execute_batch(&mut self, operations: &[Operation]) {
+        let _ = Terminal::suspend();
+
+        let mut failed = 0_usize;
+
+        for operation in operations {
+            let summary = match self.backend.execute(operation) {
+                Ok(output) => {
+                    if operation.operation_type == OperationType::Status {
+                        let _ = Terminal::print(&output.stdout);
+                    }
+
+                    if !output.success {
+                        failed = failed.saturating_add(1);
+                    }
+
+                    output.summary(operation)
+                }
+                Err(err) => {
+                    failed = failed.saturating_add(1);
+
+                    format!("{operation} could not be executed: {err}")
+                }
+            };
+
+            let _ = Terminal::print(&format!("{summary}\r\n"));
+            let _ = Terminal::execute();
+        }
+
+        let _ = Self::wait_for_enter();
+        let _ = Terminal::resume();
+        self.handle_resize_command(self.terminal_size);
+
+        let operation_type = operations
+            .first()
+            .map(|operation| operation.operation_type.to_string())
+            .unwrap_or_default();
+
+        self.message_bar.update_message(&format!(
+            "{operation_type}: {} succeeded, {failed} failed",
+            operations.len().saturating_sub(failed)
+        ));
+    }
+
+    /// Prints the output of an operation on the suspended terminal and waits for the user.
+    fn show_output(output: &OperationOutput) -> Result<(), Error> {
+        Terminal::print(&output.stdout)?;
+        Terminal::print(&output.stderr)?;
+
+        Self::wait_for_enter()
+    }
+
+    fn wait_for_enter() -> Result<(), Error> {
+        Terminal::print("\r\nPress enter to go back")?;
+        Terminal::execute()?;
+
+        stdin().read_line(&mut String::new())?;
+
+        Ok(())
+    }
+
+    fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        self.detail_pane.invalidate();
+        self.handle_resize_command(self.terminal_size);
+    }
+
+    /// Loads the status of the selected unit into the detail pane, unless it is already shown.
+    fn refresh_details(&mut self) {
+        if !self.show_details {
+            return;
         }
 
-        self.should_quit = true;
+        let selected = self.view.get_selected_service_name();
+
+        if selected.as_deref() == self.detail_pane.unit() {
+            return;
+        }
+
+        let Some(name) = selected else {
+            self.detail_pane.clear();
+            return;
+        };
+
+        let scope = self.listing.scope;
+        let properties = self.backend.properties(scope, &name);
+        let journal = self.backend.journal(
+            scope,
+            &JournalQuery {
+                unit: name.clone(),
+                lines: Some(self.detail_pane.journal_lines()),
+                ..JournalQuery::default()
+            },
+        );
+
+        self.detail_pane.update(name, properties, journal);
     }
 
     fn refresh_status(&mut self) {
-        self.status_bar
-            .update_status(self.view.get_status(self.mode));
+        let status = if Mode::is_log(self.mode) {
+            self.log_view.get_status(self.mode, self.listing)
+        } else {
+            TuiStatus {
+                quick_filter: self.quick_filter,
+                sort: self.sort,
+                ..self.view.get_status(self.mode, self.listing)
+            }
+        };
+
+        self.status_bar.update_status(status);
+    }
+
+    fn open_log(&mut self) {
+        let Some(name) = self.view.get_selected_service_name() else {
+            return;
+        };
+
+        match self
+            .log_view
+            .open(self.backend.as_ref(), self.listing.scope, name)
+        {
+            Ok(()) => {
+                self.mode = Mode::Log;
+                self.message_bar.update_message(&self.log_help_message());
+                self.handle_resize_command(self.terminal_size);
+            }
+            Err(err) => self
+                .message_bar
+                .update_message(&format!("Could not read the journal: {err}")),
+        }
+    }
+
+    fn close_log(&mut self) {
+        self.mode = Mode::Normal;
+        self.message_bar.clear_message();
+        self.handle_resize_command(self.terminal_size);
+    }
+
+    /// Time until the next refresh of the list, `None` while it is not shown.
+    fn time_to_refresh(&self) -> Option<Duration> {
+        if Mode::is_log(self.mode) {
+            return None;
+        }
+
+        self.refresh_interval
+            .map(|interval| interval.saturating_sub(self.last_refresh.elapsed()))
+    }
+
+    /// Does whatever is due without user input: reading the journal again when following it,
+    /// refreshing the list and fading the highlight of changed services.
+    fn tick(&mut self) {
+        if Mode::is_log(self.mode)
+            && self.log_view.is_following()
+            && let Err(err) = self.log_view.read_new_entries(self.backend.as_ref())
+        {
+            self.message_bar
+                .update_message(&format!("Could not read the journal: {err}"));
+        }
+
+        if self.time_to_refresh() == Some(Duration::ZERO) {
+            self.refresh_list();
+        }
+
+        self.view.clear_expired_changes();
+    }
+
+    /// How long to wait for input before calling `tick`, `None` to wait for input only.
+    fn tick_interval(&self) -> Option<Duration> {
+        let follow =
+            (Mode::is_log(self.mode) && self.log_view.is_following()).then_some(FOLLOW_INTERVAL);
+
+        [
+            follow,
+            self.time_to_refresh(),
+            self.view.changes_shown_for(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    fn searchable(&mut self) -> &mut dyn Searchable {
+        if Mode::is_log(self.mode) {
+            &mut self.log_view
+        } else {
+            &mut self.view
+        }
     }
 
     fn handle_resize_command(&mut self, size: Size) {
         self.terminal_size = size;
 
+        // The bars take three rows, the detail pane up to half of the rest.
+        let content_height = size.height.saturating_sub(3);
+        let details_height = if self.show_details {
+            min(MAX_DETAILS_HEIGHT, content_height.div_ceil(2))
+        } else {
+            0
+        };
+
         self.view.resize(Size {
-            height: size.height.saturating_sub(3),
+            height: content_height.saturating_sub(details_height),
+            width: size.width,
+        });
+        self.detail_pane.resize(Size {
+            height: details_height,
+            width: size.width,
+        });
+        self.log_view.resize(Size {
+            height: size.height.saturating_sub(2),
+            width: size.width,
+        });
+        self.confirm_dialog.resize(Size {
+            height: content_height,
             width: size.width,
         });
+        self.detail_pane.invalidate();
 
         let bar_size = Size {
             height: 1,
@@ -152,21 +654,61 @@ impl Tui {
         self.search_bar.resize(bar_size);
     }
 
-    pub fn new() -> Result<Self, Error> {
+    pub fn new(backend: Box<dyn Backend>, config: &Config) -> Result<Self, Error> {
         let current_hook = take_hook();
         set_hook(Box::new(move |panic_info| {
             let _ = Terminal::terminate();
             current_hook(panic_info);
         }));
 
+        Terminal::set_theme(config.theme.clone().adapt(ColorDepth::detect()));
         Terminal::initialize()?;
 
-        let mut tui = Self::default();
+        let mut tui = Self {
+            should_quit: false,
+            mode: Mode::default(),
+            terminal_size: Size::default(),
+            view: View::default(),
+            status_bar: StatusBar::default(),
+            filter_bar: FilterBar::default(),
+            search_bar: SearchBar::default(),
+            message_bar: MessageBar::default(),
+            detail_pane: DetailPane::default(),
+            show_details: true,
+            log_view: LogView::default(),
+            confirm_dialog: ConfirmDialog::default(),
+            confirm: config.confirm.clone(),
+            keys_page: 0,
+            pending: Vec::new(),
+            refresh_interval: config.refresh_interval,
+            last_refresh: Instant::now(),
+            picker: false,
+            picked: None,
+            multiplier: None,
+            listing: config.listing,
+            quick_filter: config.quick_filter,
+            sort: config.sort,
+            keymap: config.keymap.clone(),
+            backend,
+        };
         let size = Terminal::size().unwrap_or_default();
 
+        tui.message_bar.set_timeout(config.message_timeout);
+        tui.filter_bar.set_mode(config.filter_mode);
+        tui.search_bar.set_options(config.search);
+        tui.message_bar.set_default_message(
+            &tui.help_message(&LISTING_ACTIONS),
+            &format!(
+                "{} | exit: ctrl+q",
+                tui.help_message(&[Action::ShowKeys, Action::Help])
+            ),
+        );
         tui.view.set_hilight_selected_line(true);
         tui.handle_resize_command(size);
-        tui.view.load(tui.target)?;
+        // A manager that can not be reached, e.g. the user one under sudo, is reported like
+        // switching to it would be, so the other one can still be picked.
+        tui.switch_listing(tui.listing);
+        tui.refresh_details();
         tui.refresh_status();
 
         Terminal::set_title("systemctl-manager")?;
@@ -180,7 +722,12 @@ impl Tui {
         }
 
         let _ = Terminal::hide_caret();
-        self.filter_bar.render(0);
+
+        if Mode::is_log(self.mode) {
+            self.log_view.render(0);
+        } else {
+            self.filter_bar.render(0);
+        }
 
         if self.terminal_size.height > 1 {
             self.status_bar
@@ -197,8 +744,15 @@ impl Tui {
             }
         }
 
-        if self.terminal_size.height > 3 {
+        if self.terminal_size.height > 3 && self.mode == Mode::Confirm {
+            self.confirm_dialog.render(1);
+        } else if self.terminal_size.height > 3 && !Mode::is_log(self.mode) {
             self.view.render(1);
+
+            if self.show_details {
+                self.detail_pane
+                    .render(self.view.height().saturating_add(1));
+            }
         }
 
         if Mode::is_filter(self.mode) {
@@ -217,104 +771,144 @@ impl Tui {
         let _ = Terminal::execute();
     }
 
-    fn process_command_during_normal(&mut self, command: Command) {
-        if matches!(command, System(Quit)) {
-            self.should_quit = true;
-            return;
-        }
+    /// Enter picks the marked units or the selected one, dismissing without marks cancels.
+    /// Returns whether the command was handled.
+    fn process_picker_command(&mut self, command: Command) -> bool {
+        match command {
+            Edit(InsertNewLine) => {
+                let mut names = self.view.get_marked_service_names();
 
-        if let Edit(Insert('0'..='9')) = command
-            && let Edit(Insert(ch)) = command
-        {
-            self.append_multiplier(ch);
+                if names.is_empty()
+                    && let Some(name) = self.view.get_selected_service_name()
+                {
+                    names.push(name);
+                }
 
-            if let Some(multiplier) = &self.multiplier {
-                self.message_bar.update_message(multiplier);
+                if !names.is_empty() {
+                    self.picked = Some(names);
+                    self.should_quit = true;
+                }
+
+                true
+            }
+            System(Dismiss) if self.view.get_marked_service_names().is_empty() => {
+                self.should_quit = true;
+                true
             }
+            _ => false,
+        }
+    }
 
-            return;
+    fn move_selection(&mut self, move_command: command::Move) {
+        let multiplier = self.get_multiplier();
+        self.view.handle_move_command(move_command, multiplier);
+
+        if multiplier.is_some() {
+            self.message_bar.clear_message();
         }
+    }
 
-        match command {
-            Edit(Insert('/')) => {
+    fn process_normal_action(&mut self, action: Action) {
+        if action != Action::ShowKeys {
+            self.keys_page = 0;
+        }
+
+        match action {
+            Action::Search => {
                 self.mode = Mode::Search;
                 self.view.enter_search();
                 self.search_bar.redraw();
             }
-            Edit(Insert('i' | 'I' | 'a' | 'A')) => {
+            Action::Logs => self.open_log(),
+            Action::Filter => {
                 self.mode = Mode::Filter;
 
                 self.view.set_hilight_selected_line(false);
                 self.view.scroll_to_start();
-                self.message_bar.clear_message();
-            }
-            Edit(Insert('p')) => {
-                self.message_bar.update_message("filter mode: i/a/I/A | search mode: / | dismiss: ctrl+c/esc | confirm: enter | search next: n | search prev: N");
+                self.message_bar
+                    .update_message("fuzzy/prefix/substring: tab | back: enter/esc");
             }
-            Edit(Insert('o')) => {
-                self.message_bar.update_message("status: q | start: w | stop: e | reload: r | restart: t | enable: y | disable: u");
+            Action::Help => {
+                let message = format!(
+                    "{} | clear marks: esc | dismiss: ctrl+c/esc | confirm: enter",
+                    self.help_message(&HELP_ACTIONS)
+                );
+                self.message_bar.update_message(&message);
             }
-            Edit(Insert('f')) => {
-                self.filter_bar.clear_value();
-                self.view.scroll_to_start();
+            Action::ShowKeys => {
+                let pages: [&[Action]; 3] =
+                    [&UNIT_ACTIONS, &MORE_UNIT_ACTIONS, &FILTER_SORT_ACTIONS];
+                let page = pages.get(self.keys_page).copied().unwrap_or_default();
+                let mut message = self.help_message(page);
 
-                // WARNING:
-                // This line executes a command that can fail.
-                // If the program runs until here, there should be no reason for that command to fail,
-                // because this same operation was already executed during the creation of the Tui struct.
-                let _ = self.toggle_target();
-            }
-            Edit(Insert('n')) => {
-                self.view.search_next();
-            }
-            Edit(Insert('N')) => {
-                self.view.search_prev();
-            }
-            Edit(Insert('q')) => {
-                self.set_operation_result(OperationType::Status);
-            }
-            Edit(Insert('w')) => {
-                self.set_operation_result(OperationType::Start);
-            }
-            Edit(Insert('e')) => {
-                self.set_operation_result(OperationType::Stop);
-            }
-            Edit(Insert('r')) => {
-                self.set_operation_result(OperationType::Reload);
-            }
-            Edit(Insert('t')) => {
-                self.set_operation_result(OperationType::Restart);
+                self.keys_page = self.keys_page.saturating_add(1);
+
+                if self.keys_page < pages.len() {
+                    message = format!(
+                        "{message} | more: {}",
+                        self.keymap.keys(Mode::Normal, Action::ShowKeys)
+                    );
+                } else {
+                    self.keys_page = 0;
+                }
+
+                self.message_bar.update_message(&message);
             }
-            Edit(Insert('y')) => {
-                self.set_operation_result(OperationType::Enable);
+            Action::ToggleTarget | Action::ToggleScope | Action::NextUnitType => {
+                self.process_listing_action(action);
             }
-            Edit(Insert('u')) => {
-                self.set_operation_result(OperationType::Disable);
+            Action::SearchNext => self.view.search_next(),
+            Action::SearchPrev => self.view.search_prev(),
+            Action::Details => self.toggle_details(),
+            Action::Operation(operation_type) => self.process_operation_action(operation_type),
+            Action::QuickFilter(quick_filter) => self.toggle_quick_filter(quick_filter),
+            Action::Sort | Action::ReverseSort => self.process_sort_action(action),
+            Action::Mark | Action::MarkRange | Action::MarkAll => self.process_mark_action(action),
+            Action::Refresh => {
+                self.view.expire_details();
+                self.refresh_list();
             }
-            Edit(Insert('j')) => {
-                let multiplier = self.get_multiplier();
-                self.view.handle_move_command(Down, multiplier);
+            Action::Down => self.move_selection(Down),
+            Action::Up => self.move_selection(Up),
+            Action::Top
+            | Action::Bottom
+            | Action::Follow
+            | Action::Priority
+            | Action::TimeRange
+            | Action::Close => {}
+        }
+    }
 
-                if multiplier.is_some() {
-                    self.message_bar.clear_message();
-                }
-            }
-            Edit(Insert('k')) => {
-                let multiplier = self.get_multiplier();
-                self.view.handle_move_command(Up, multiplier);
+    fn process_command_during_normal(&mut self, command: Command) {
+        if matches!(command, System(Quit)) {
+            self.should_quit = true;
+            return;
+        }
 
-                if multiplier.is_some() {
-                    self.message_bar.clear_message();
-                }
+        if self.picker && self.process_picker_command(command) {
+            return;
+        }
+
+        if let Edit(Insert('0'..='9')) = command
+            && let Edit(Insert(ch)) = command
+        {
+            self.append_multiplier(ch);
+
+            if let Some(multiplier) = &self.multiplier {
+                self.message_bar.update_message(multiplier);
             }
-            Move(move_command) => {
-                let multiplier = self.get_multiplier();
-                self.view.handle_move_command(move_command, multiplier);
 
-                if multiplier.is_some() {
-                    self.message_bar.clear_message();
+            return;
+        }
+
+        match command {
+            Edit(Insert(key)) => {
+                if let Some(action) = self.keymap.action(Mode::Normal, key) {
+                    self.process_normal_action(action);
                 }
             }
+            System(Dismiss) => self.view.clear_marks(),
+            Move(move_command) => self.move_selection(move_command),
             _ => {}
         }
 
@@ -322,48 +916,224 @@ impl Tui {
         self.multiplier.take();
     }
 
+    /// Filters the view, reloading it when the filter needs fields it was loaded without.
+    fn filter_view(&mut self) {
+        if self
+            .view
+            .lacks_details(self.filter_bar.query(), self.quick_filter)
+        {
+            self.refresh_list();
+            return;
+        }
+
+        self.view.filter(
+            self.filter_bar.query(),
+            self.filter_bar.mode(),
+            self.quick_filter,
+        );
+    }
+
+    /// Shows only the units of `quick_filter`, or all of them again when it was shown
+    /// already, staying on the selected unit if it is still shown.
+    fn toggle_quick_filter(&mut self, quick_filter: QuickFilter) {
+        let selected = self.view.get_selected_service_name();
+
+        self.quick_filter = (self.quick_filter != Some(quick_filter)).then_some(quick_filter);
+        self.filter_view();
+        self.view.select(selected.as_deref());
+        self.detail_pane.invalidate();
+    }
+
+    /// Sorts by the next key, or in the other direction, staying on the selected unit.
+    fn process_sort_action(&mut self, action: Action) {
+        if action == Action::Sort {
+            self.sort.key = self.sort.key.next(self.listing.target);
+        } else {
+            self.sort.descending = !self.sort.descending;
+        }
+
+        self.message_bar
+            .update_message(&format!("sorted by {}", self.sort));
+        self.refresh_list();
+    }
+
     fn process_command_during_filter(&mut self, command: Command) {
         match command {
             System(Dismiss) | Edit(InsertNewLine) => {
                 self.mode = Mode::Normal;
 
                 self.view.set_hilight_selected_line(true);
+                self.message_bar.clear_message();
+            }
+            // A tab can not be part of a unit name, it switches how the query matches.
+            Edit(Insert('\t')) => {
+                self.filter_bar.set_mode(self.filter_bar.mode().next());
+                self.filter_view();
             }
             Edit(command) => {
                 self.filter_bar.handle_edit_command(command);
-                self.view.filter(&self.filter_bar.value());
+                self.filter_view();
             }
             _ => {}
         }
     }
 
+    /// Searches for the value of the search bar. An invalid pattern is reported in the bar
+    /// and keeps the matches of the last valid one.
+    fn search_with_bar(&mut self) {
+        let query = self.search_bar.value();
+
+        if query.is_empty() {
+            self.search_bar.set_error(None);
+            self.searchable().search(None);
+            return;
+        }
+
+        match SearchPattern::new(&query, self.search_bar.options()) {
+            Ok(pattern) => {
+                self.search_bar.set_error(None);
+                self.searchable().search(Some(pattern));
+            }
+            Err(err) => self.search_bar.set_error(Some(err)),
+        }
+    }
+
     fn process_command_during_search(&mut self, command: Command) {
+        let mode_after_search = if Mode::is_log(self.mode) {
+            Mode::Log
+        } else {
+            Mode::Normal
+        };
+
         match command {
             System(Dismiss) => {
-                self.mode = Mode::Normal;
-                self.view.dismiss_search();
+                self.mode = mode_after_search;
+                self.searchable().dismiss_search();
                 self.message_bar.redraw();
                 self.search_bar.clear_value();
             }
             Edit(InsertNewLine) => {
-                self.mode = Mode::Normal;
-                self.view.exit_search();
+                self.mode = mode_after_search;
+                self.searchable().exit_search();
                 self.message_bar.redraw();
             }
             Edit(command) => {
                 self.search_bar.handle_edit_command(command);
-                self.view.search(&self.search_bar.value());
+                self.search_with_bar();
+            }
+            System(toggle @ (ToggleRegex | ToggleCase | ToggleWholeWord)) => {
+                let mut options = self.search_bar.options();
+
+                match toggle {
+                    ToggleRegex => options.regex = !options.regex,
+                    ToggleCase => options.match_case = !options.match_case,
+                    _ => options.whole_word = !options.whole_word,
+                }
+
+                self.search_bar.set_options(options);
+                self.search_with_bar();
             }
             Move(Down) => {
-                self.view.search_next();
+                self.searchable().search_next();
             }
             Move(Up) => {
-                self.view.search_prev();
+                self.searchable().search_prev();
+            }
+            _ => {}
+        }
+    }
+
+    fn process_journal_query_action(&mut self, action: Action) {
+        let backend = self.backend.as_ref();
+        let result = match action {
+            Action::Priority => self.log_view.next_priority(backend),
+            Action::TimeRange => self.log_view.next_time_range(backend),
+            _ => return,
+        };
+
+        if let Err(err) = result {
+            self.message_bar
+                .update_message(&format!("Could not read the journal: {err}"));
+        }
+    }
+
+    fn process_log_action(&mut self, action: Action) {
+        match action {
+            Action::Close => self.close_log(),
+            Action::Search => {
+                self.mode = Mode::LogSearch;
+                self.log_view.enter_search();
+                self.search_bar.redraw();
+            }
+            Action::SearchNext => self.log_view.search_next(),
+            Action::SearchPrev => self.log_view.search_prev(),
+            Action::Down => self.log_view.handle_move_command(Down, None),
+            Action::Up => self.log_view.handle_move_command(Up, None),
+            Action::Top => self.log_view.scroll_to_start(),
+            Action::Bottom => self.log_view.scroll_to_end(),
+            Action::Follow => self.log_view.toggle_follow(),
+            Action::Priority | Action::TimeRange => self.process_journal_query_action(action),
+            Action::Help => {
+                let message = self.log_help_message();
+                self.message_bar.update_message(&message);
             }
             _ => {}
         }
     }
 
+    fn process_command_during_log(&mut self, command: Command) {
+        match command {
+            System(Quit) => self.should_quit = true,
+            System(Dismiss) => self.close_log(),
+            Edit(Insert(key)) => {
+                if let Some(action) = self.keymap.action(Mode::Log, key) {
+                    self.process_log_action(action);
+                }
+            }
+            Move(move_command) => self.log_view.handle_move_command(move_command, None),
+            _ => {}
+        }
+
+        self.message_bar.redraw();
+    }
+
+    fn process_command_during_confirm(&mut self, command: Command) {
+        let operations = std::mem::take(&mut self.pending);
+
+        self.mode = Mode::Normal;
+        self.handle_resize_command(self.terminal_size);
+
+        match command {
+            System(Quit) => self.should_quit = true,
+            Edit(Insert('y' | 'Y')) => self.execute_operations(&operations),
+            _ => self.message_bar.update_message("Cancelled"),
+        }
+    }
+
+    fn process_command_during_signal(&mut self, command: Command) {
+        self.mode = Mode::Normal;
+
+        let signal = match command {
+            System(Quit) => {
+                self.should_quit = true;
+                return;
+            }
+            Edit(Insert(key)) => SIGNAL_KEYS
+                .iter()
+                .find(|(signal_key, _)| *signal_key == key)
+                .map(|(_, signal)| *signal),
+            _ => None,
+        };
+
+        match signal {
+            Some(signal) => {
+                self.message_bar.clear_message();
+                self.run_operation(OperationType::Kill(signal));
+            }
+            None => self.message_bar.update_message("Cancelled"),
+        }
+    }
+
     fn process_command(&mut self, command: Command) {
         if let System(Resize(size)) = command {
             self.handle_resize_command(size);
@@ -373,7 +1143,10 @@ impl Tui {
         match self.mode {
             Mode::Normal => self.process_command_during_normal(command),
             Mode::Filter => self.process_command_during_filter(command),
-            Mode::Search => self.process_command_during_search(command),
+            Mode::Search | Mode::LogSearch => self.process_command_during_search(command),
+            Mode::Log => self.process_command_during_log(command),
+            Mode::Confirm => self.process_command_during_confirm(command),
+            Mode::Signal => self.process_command_during_signal(command),
         }
     }
 
@@ -389,6 +1162,17 @@ impl Tui {
         }
     }
 
+    /// Runs the interface as a chooser and returns the picked units, `None` when cancelled.
+    pub fn pick(&mut self) -> Option<Vec<String>> {
+        self.picker = true;
+        self.message_bar
+            .update_message("pick the selected or marked units: enter | cancel: esc");
+
+        self.run();
+
+        self.picked.take()
+    }
+
     #[allow(clippy::print_stdout)]
     pub fn run(&mut self) {
         loop {
@@ -398,9 +1182,16 @@ impl Tui {
                 break;
             }
 
-            match read() {
-                Ok(event) => self.evaluate_event(event),
-                Err(err) => {
+            let event = match self.tick_interval().map(poll) {
+                Some(Ok(false)) => None,
+                Some(Err(err)) => Some(Err(err)),
+                Some(Ok(true)) | None => Some(read()),
+            };
+
+            match event {
+                None => self.tick(),
+                Some(Ok(event)) => self.evaluate_event(event),
+                Some(Err(err)) => {
                     #[cfg(debug_assertions)]
                     {
                         panic!("Could not read event: {err:?}")
@@ -413,6 +1204,12 @@ impl Tui {
                 }
             }
 
+            // Reading the status of a unit takes a moment, so it is skipped while keys are
+            // still queued, e.g. when holding j.
+            if !poll(Duration::ZERO).unwrap_or_default() {
+                self.refresh_details();
+            }
+
             self.refresh_status();
         }
     }
@@ -1,5 +1,4 @@
 use crate::prelude::*;
-use std::cmp::min;
 
 use super::{AnnotatedString, AnnotatedStringPart};
 
@@ -11,46 +10,33 @@ pub struct AnnotatedStringIterator<'a> {
 impl<'a> Iterator for AnnotatedStringIterator<'a> {
     type Item = AnnotatedStringPart<'a>;
 
+    /// Parts end wherever an annotation starts or ends, so the same annotations cover
+    /// all of a part.
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current_idx >= self.annotated_string.string.len() {
-            return None;
-        }
+        let len = self.annotated_string.string.len();
 
-        if let Some(annotation) = self
-            .annotated_string
-            .annotattions
-            .iter()
-            .filter(|annotation| {
-                annotation.start <= self.current_idx && annotation.end > self.current_idx
-            })
-            .next_back()
-        {
-            let end_idx = min(annotation.end, self.annotated_string.string.len());
-            let start_idx = self.current_idx;
-
-            self.current_idx = end_idx;
-
-            return Some(AnnotatedStringPart {
-                string: &self.annotated_string.string[start_idx..end_idx],
-                annotation_type: Some(annotation.annotation_type),
-            });
+        if self.current_idx >= len {
+            return None;
         }
 
-        let mut end_idx = self.annotated_string.string.len();
+        let start_idx = self.current_idx;
+        let mut end_idx = len;
+        let mut annotation_types = Vec::new();
 
         for annotation in &self.annotated_string.annotattions {
-            if annotation.start > self.current_idx && annotation.start < end_idx {
-                end_idx = annotation.start;
+            if annotation.start <= start_idx && annotation.end > start_idx {
+                annotation_types.push(annotation.annotation_type);
+                end_idx = end_idx.min(annotation.end);
+            } else if annotation.start > start_idx {
+                end_idx = end_idx.min(annotation.start);
             }
         }
 
-        let start_idx = self.current_idx;
-
         self.current_idx = end_idx;
 
         Some(AnnotatedStringPart {
             string: &self.annotated_string.string[start_idx..end_idx],
-            annotation_type: None,
+            annotation_types,
         })
     }
 }
@@ -3,5 +3,6 @@ use super::super::AnnotationType;
 #[derive(Debug)]
 pub struct AnnotatedStringPart<'a> {
     pub string: &'a str,
-    pub annotation_type: Option<AnnotationType>,
+    /// Every annotation covering the part, the ones added last on top.
+    pub annotation_types: Vec<AnnotationType>,
 }
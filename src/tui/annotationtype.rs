@@ -1,6 +1,16 @@
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
 pub enum AnnotationType {
     Match,
+    /// A character of the name the filter matched.
+    FilterMatch,
     Selected,
     SelectedMatch,
+    Marked,
+    SelectedMarked,
+    Changed,
+    Failed,
+    Active,
+    Inactive,
+    Activating,
+    Masked,
 }
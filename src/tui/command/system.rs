@@ -13,6 +13,11 @@ pub enum System {
     Quit,
     Dismiss,
     Search,
+    /// Switches between regex and literal search.
+    ToggleRegex,
+    /// Switches between matching case and smart case search.
+    ToggleCase,
+    ToggleWholeWord,
 }
 
 impl TryFrom<KeyEvent> for System {
@@ -31,6 +36,14 @@ impl TryFrom<KeyEvent> for System {
                 Char('c') => Ok(Self::Dismiss),
                 _ => Err(format!("Unsupported CONTROL+{code:?} combination")),
             }
+        } else if modifiers == KeyModifiers::ALT {
+            // The same keys as the search options of most editors.
+            match code {
+                Char('r') => Ok(Self::ToggleRegex),
+                Char('c') => Ok(Self::ToggleCase),
+                Char('w') => Ok(Self::ToggleWholeWord),
+                _ => Err(format!("Unsupported ALT+{code:?} combination")),
+            }
         } else if modifiers == KeyModifiers::NONE && matches!(code, KeyCode::Esc) {
             Ok(Self::Dismiss)
         } else {
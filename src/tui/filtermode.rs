@@ -0,0 +1,154 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+mod fuzzy;
+
+use crate::prelude::*;
+
+const CYCLE: [FilterMode; 3] = [FilterMode::Fuzzy, FilterMode::Prefix, FilterMode::Substring];
+
+/// How the filter query is matched against unit names. Queries without capitals ignore case.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum FilterMode {
+    /// The query is a subsequence of the name, units are ranked by how well it matches.
+    #[default]
+    Fuzzy,
+    Prefix,
+    Substring,
+}
+
+/// Where the query matched a name, and how well.
+pub struct FilterMatch {
+    pub score: i32,
+    /// Byte indices of the matched characters of the name.
+    pub positions: Vec<ByteIdx>,
+}
+
+impl Display for FilterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fuzzy => write!(f, "fuzzy"),
+            Self::Prefix => write!(f, "prefix"),
+            Self::Substring => write!(f, "substring"),
+        }
+    }
+}
+
+impl FromStr for FilterMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        CYCLE
+            .into_iter()
+            .find(|mode| mode.to_string() == value)
+            .ok_or_else(|| {
+                format!("Unknown filter mode '{value}', expected one of: fuzzy, prefix, substring")
+            })
+    }
+}
+
+/// Compares ignoring case when `ignore_case` is set.
+fn same_char(a: char, b: char, ignore_case: bool) -> bool {
+    a == b || (ignore_case && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+impl FilterMode {
+    pub fn next(self) -> Self {
+        let idx = CYCLE
+            .iter()
+            .position(|mode| *mode == self)
+            .map_or(0, |idx| idx.saturating_add(1));
+
+        CYCLE.get(idx).copied().unwrap_or_default()
+    }
+
+    /// How `query` matches `name`, `None` when it does not.
+    pub fn find(self, query: &str, name: &str) -> Option<FilterMatch> {
+        let ignore_case = !query.chars().any(char::is_uppercase);
+        let query: Vec<char> = query.chars().collect();
+        let chars: Vec<(ByteIdx, char)> = name.char_indices().collect();
+
+        let matches_at = |start: usize| {
+            query.len() <= chars.len().saturating_sub(start)
+                && chars
+                    .iter()
+                    .skip(start)
+                    .zip(&query)
+                    .all(|((_, ch), query_ch)| same_char(*ch, *query_ch, ignore_case))
+        };
+        let run = |start: usize| FilterMatch {
+            score: 0,
+            positions: chars
+                .iter()
+                .skip(start)
+                .take(query.len())
+                .map(|(idx, _)| *idx)
+                .collect(),
+        };
+
+        match self {
+            Self::Fuzzy => fuzzy::find(&query, &chars, ignore_case),
+            Self::Prefix => matches_at(0).then(|| run(0)),
+            Self::Substring => (0..chars.len()).find(|start| matches_at(*start)).map(run),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(mode: FilterMode, query: &str, name: &str) -> Option<Vec<ByteIdx>> {
+        mode.find(query, name).map(|found| found.positions)
+    }
+
+    #[test]
+    fn matches_prefixes() {
+        assert_eq!(
+            positions(FilterMode::Prefix, "ngi", "nginx.service"),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(positions(FilterMode::Prefix, "gin", "nginx.service"), None);
+        assert_eq!(
+            positions(FilterMode::Prefix, "nginx.services", "nginx.service"),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_substrings() {
+        assert_eq!(
+            positions(FilterMode::Substring, "gin", "nginx.service"),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            positions(FilterMode::Substring, "ice", "nginx.service"),
+            Some(vec![10, 11, 12])
+        );
+        assert_eq!(
+            positions(FilterMode::Substring, "ngx", "nginx.service"),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_case_unless_the_query_has_capitals() {
+        assert!(FilterMode::Prefix.find("open", "OpenSSH.service").is_some());
+        assert!(FilterMode::Prefix.find("OPEN", "OpenSSH.service").is_none());
+        assert!(
+            FilterMode::Substring
+                .find("SSH", "OpenSSH.service")
+                .is_some()
+        );
+        assert!(FilterMode::Substring.find("SSH", "ssh.service").is_none());
+    }
+
+    #[test]
+    fn cycles_through_the_modes() {
+        assert!(FilterMode::default() == FilterMode::Fuzzy);
+        assert!(FilterMode::Fuzzy.next() == FilterMode::Prefix);
+        assert!(FilterMode::Substring.next() == FilterMode::Fuzzy);
+    }
+}
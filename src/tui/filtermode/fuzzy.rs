@@ -0,0 +1,154 @@
+use super::{FilterMatch, same_char};
+use crate::prelude::*;
+
+/// Score of every matched character.
+const MATCH: i32 = 16;
+/// Bonus for a character right after the previous match.
+const CONSECUTIVE: i32 = 8;
+/// Bonus for a character starting a word of the name, doubled at its very start.
+const BOUNDARY: i32 = 8;
+/// Penalty for skipping characters between two matches, and for each one skipped after the first.
+const GAP_START: i32 = 3;
+const GAP_EXTENSION: i32 = 1;
+
+/// Finds `query` as a subsequence of `chars`, the characters of a name with their byte indices.
+///
+/// The first complete match is found left to right, then shortened right to left, so that
+/// `sshd` picks the `sshd` in `systemd-sshd` rather than letters scattered over `systemd`.
+pub fn find(query: &[char], chars: &[(ByteIdx, char)], ignore_case: bool) -> Option<FilterMatch> {
+    let mut remaining = query.iter().peekable();
+    let mut end = None;
+
+    for (idx, (_, ch)) in chars.iter().enumerate() {
+        if remaining
+            .next_if(|query_ch| same_char(*ch, **query_ch, ignore_case))
+            .is_some()
+            && remaining.peek().is_none()
+        {
+            end = Some(idx);
+            break;
+        }
+    }
+
+    let mut remaining = query.iter().rev().peekable();
+    let mut matched = Vec::with_capacity(query.len());
+
+    for (idx, (_, ch)) in chars.iter().enumerate().take(end?.saturating_add(1)).rev() {
+        if remaining
+            .next_if(|query_ch| same_char(*ch, **query_ch, ignore_case))
+            .is_some()
+        {
+            matched.push(idx);
+
+            if remaining.peek().is_none() {
+                break;
+            }
+        }
+    }
+
+    matched.reverse();
+
+    Some(FilterMatch {
+        score: score(chars, &matched),
+        positions: matched
+            .iter()
+            .filter_map(|idx| chars.get(*idx).map(|(byte_idx, _)| *byte_idx))
+            .collect(),
+    })
+}
+
+/// Rewards matches that are consecutive or start words, and penalizes gaps between them.
+fn score(chars: &[(ByteIdx, char)], matched: &[usize]) -> i32 {
+    let mut score = 0_i32;
+    let mut previous: Option<usize> = None;
+
+    for idx in matched {
+        score = score.saturating_add(MATCH);
+
+        if *idx == 0 {
+            score = score.saturating_add(BOUNDARY.saturating_mul(2));
+        } else if idx
+            .checked_sub(1)
+            .and_then(|before| chars.get(before))
+            .is_some_and(|(_, ch)| !ch.is_alphanumeric())
+        {
+            score = score.saturating_add(BOUNDARY);
+        }
+
+        if let Some(previous) = previous {
+            let gap = idx.saturating_sub(previous).saturating_sub(1);
+
+            if gap == 0 {
+                score = score.saturating_add(CONSECUTIVE);
+            } else {
+                let extension = i32::try_from(gap.saturating_sub(1)).unwrap_or(i32::MAX);
+
+                score = score
+                    .saturating_sub(GAP_START)
+                    .saturating_sub(extension.saturating_mul(GAP_EXTENSION));
+            }
+        }
+
+        previous = Some(*idx);
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::FilterMode;
+
+    fn score(query: &str, name: &str) -> i32 {
+        FilterMode::Fuzzy
+            .find(query, name)
+            .map_or(i32::MIN, |found| found.score)
+    }
+
+    fn positions(query: &str, name: &str) -> Vec<usize> {
+        FilterMode::Fuzzy
+            .find(query, name)
+            .map(|found| found.positions)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn ranks_word_starts_above_the_middle_of_words() {
+        assert!(score("ssh", "ssh.service") > score("ssh", "openssh.service"));
+        assert!(score("ssh", "user-ssh.service") > score("ssh", "openssh.service"));
+        assert!(score("ssh", "ssh.service") > score("ssh", "user-ssh.service"));
+    }
+
+    #[test]
+    fn ranks_consecutive_matches_above_scattered_ones() {
+        assert!(score("net", "network.service") > score("net", "nginx-exporter.service"));
+        assert!(score("cron", "cron.service") > score("cron", "cups-browsed-on.service"));
+        // A longer gap costs more than a short one.
+        assert!(score("ab", "a-xb") > score("ab", "a-xxxb"));
+    }
+
+    #[test]
+    fn picks_the_tightest_match() {
+        assert_eq!(positions("sshd", "systemd-sshd.service"), [8, 9, 10, 11]);
+        assert_eq!(positions("sd", "systemd-sshd"), [2, 6]);
+    }
+
+    #[test]
+    fn needs_every_character_in_order() {
+        assert!(FilterMode::Fuzzy.find("xyz", "nginx.service").is_none());
+        assert!(FilterMode::Fuzzy.find("dc", "cd").is_none());
+        assert!(FilterMode::Fuzzy.find("nginxx", "nginx").is_none());
+    }
+
+    #[test]
+    fn ignores_case_unless_the_query_has_capitals() {
+        assert_eq!(positions("ssh", "OpenSSH.service"), [4, 5, 6]);
+        assert!(FilterMode::Fuzzy.find("SSH", "ssh.service").is_none());
+        assert_eq!(positions("SSH", "OpenSSH.service"), [4, 5, 6]);
+    }
+
+    #[test]
+    fn reports_byte_positions() {
+        assert_eq!(positions("éx", "café-x"), [3, 6]);
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    cmp::Reverse,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+mod parser;
+
+use super::FilterMode;
+use super::filtermode::FilterMatch;
+use crate::systemd::Service;
+
+const FIELDS: [Field; 6] = [
+    Field::Name,
+    Field::Description,
+    Field::State,
+    Field::Load,
+    Field::Enabled,
+    Field::Preset,
+];
+
+/// A unit field a term can be restricted to, as in `state:failed`.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Field {
+    /// Contains the value.
+    Name,
+    /// Contains the value.
+    Description,
+    /// Active or sub state is the value, e.g. `failed` or `running`.
+    State,
+    Load,
+    /// `yes` or `no`, or the unit file state, e.g. `static` or `masked`.
+    Enabled,
+    Preset,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Description => write!(f, "desc"),
+            Self::State => write!(f, "state"),
+            Self::Load => write!(f, "load"),
+            Self::Enabled => write!(f, "enabled"),
+            Self::Preset => write!(f, "preset"),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "description" {
+            return Ok(Self::Description);
+        }
+
+        FIELDS
+            .into_iter()
+            .find(|field| field.to_string() == value)
+            .ok_or_else(|| {
+                format!(
+                    "unknown field '{value}', try {}",
+                    FIELDS.map(|field| field.to_string()).join(", ")
+                )
+            })
+    }
+}
+
+enum Term {
+    /// Matched against the name in the current filter mode.
+    Word(String),
+    Field(Field, String),
+}
+
+enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+/// A parsed filter, e.g. `state:failed name:nginx enabled:yes !desc:timer`.
+///
+/// Terms next to each other must all match, `|` or `OR` between them is enough for one of
+/// them, and `!` or `NOT` before one negates it. Parentheses group terms.
+#[derive(Default)]
+pub struct FilterQuery {
+    expr: Option<Expr>,
+}
+
+impl FromStr for FilterQuery {
+    type Err = String;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            expr: parser::parse(query)?,
+        })
+    }
+}
+
+/// Compares ignoring case, unless `value` has capitals.
+fn same_text(text: &str, value: &str) -> bool {
+    if value.chars().any(char::is_uppercase) {
+        text == value
+    } else {
+        text.to_lowercase() == value
+    }
+}
+
+fn matched(is_match: bool) -> Option<FilterMatch> {
+    is_match.then(|| FilterMatch {
+        score: 0,
+        positions: Vec::new(),
+    })
+}
+
+impl Field {
+    /// Whether `Backend::fill_details` has to fill the field in for loaded units.
+    fn needs_details(self) -> bool {
+        matches!(self, Self::Enabled | Self::Preset)
+    }
+}
+
+impl Term {
+    fn find(&self, service: &Service, mode: FilterMode) -> Option<FilterMatch> {
+        match self {
+            Self::Word(word) => mode.find(word, &service.name),
+            Self::Field(Field::Name, value) => FilterMode::Substring.find(value, &service.name),
+            Self::Field(Field::Description, value) => matched(
+                FilterMode::Substring
+                    .find(value, &service.description)
+                    .is_some(),
+            ),
+            Self::Field(Field::State, value) => matched(
+                same_text(&service.active_state, value) || same_text(&service.sub_state, value),
+            ),
+            Self::Field(Field::Load, value) => matched(same_text(&service.load_state, value)),
+            Self::Field(Field::Enabled, value) => {
+                let is_enabled = service.unit_file_state.starts_with("enabled");
+
+                matched(match value.to_lowercase().as_str() {
+                    "yes" | "true" => is_enabled,
+                    "no" | "false" => !is_enabled,
+                    _ => same_text(&service.unit_file_state, value),
+                })
+            }
+            Self::Field(Field::Preset, value) => matched(same_text(&service.vendor_preset, value)),
+        }
+    }
+}
+
+impl Expr {
+    fn needs_details(&self) -> bool {
+        match self {
+            Self::Term(Term::Word(_)) => false,
+            Self::Term(Term::Field(field, _)) => field.needs_details(),
+            Self::Not(expr) => expr.needs_details(),
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(Self::needs_details),
+        }
+    }
+
+    /// How the expression matches `service`. Scores of the terms add up, and the name
+    /// positions of every matching term are kept for highlighting.
+    fn find(&self, service: &Service, mode: FilterMode) -> Option<FilterMatch> {
+        match self {
+            Self::Term(term) => term.find(service, mode),
+            Self::Not(expr) => matched(expr.find(service, mode).is_none()),
+            Self::And(exprs) => exprs.iter().try_fold(
+                FilterMatch {
+                    score: 0,
+                    positions: Vec::new(),
+                },
+                |mut all, expr| {
+                    let found = expr.find(service, mode)?;
+                    all.score = all.score.saturating_add(found.score);
+                    all.positions.extend(found.positions);
+
+                    Some(all)
+                },
+            ),
+            Self::Or(exprs) => exprs
+                .iter()
+                .filter_map(|expr| expr.find(service, mode))
+                .reduce(|mut best, found| {
+                    best.score = best.score.max(found.score);
+                    best.positions.extend(found.positions);
+
+                    best
+                }),
+        }
+    }
+}
+
+impl FilterQuery {
+    pub fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    /// Whether the query looks at the unit file state or the vendor preset, which
+    /// `Backend::fill_details` has to fill in for loaded units.
+    pub fn needs_details(&self) -> bool {
+        self.expr.as_ref().is_some_and(Expr::needs_details)
+    }
+
+    /// How the query matches `service`, `None` when it does not.
+    pub fn find(&self, service: &Service, mode: FilterMode) -> Option<FilterMatch> {
+        let mut found = match &self.expr {
+            Some(expr) => expr.find(service, mode)?,
+            None => matched(true)?,
+        };
+
+        found.positions.sort_unstable();
+        found.positions.dedup();
+
+        Some(found)
+    }
+
+    /// The `items` whose service matches, with how it did, best matches first in fuzzy mode.
+    pub fn rank<T>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        mode: FilterMode,
+        service: impl Fn(&T) -> &Service,
+    ) -> Vec<(FilterMatch, T)> {
+        let mut matches: Vec<(FilterMatch, T)> = items
+            .into_iter()
+            .filter_map(|item| Some((self.find(service(&item), mode)?, item)))
+            .collect();
+
+        // The sort is stable, equally good matches stay in listing order.
+        if mode == FilterMode::Fuzzy {
+            matches.sort_by_key(|(found, _)| Reverse(found.score));
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nginx() -> Service {
+        Service {
+            name: String::from("nginx.service"),
+            load_state: String::from("loaded"),
+            active_state: String::from("failed"),
+            sub_state: String::from("failed"),
+            description: String::from("A high performance web server"),
+            unit_file_state: String::from("enabled"),
+            vendor_preset: String::from("disabled"),
+            ..Service::default()
+        }
+    }
+
+    fn matches(query: &str) -> bool {
+        let query: FilterQuery = query.parse().unwrap();
+
+        query.find(&nginx(), FilterMode::Substring).is_some()
+    }
+
+    #[test]
+    fn matches_fields() {
+        assert!(matches("state:failed name:nginx enabled:yes"));
+        assert!(matches("load:loaded preset:disabled enabled:enabled"));
+        assert!(matches("desc:web !desc:timer"));
+        assert!(!matches("enabled:no"));
+        assert!(!matches("name:apache"));
+    }
+
+    #[test]
+    fn ignores_case_unless_the_value_has_capitals() {
+        assert!(matches(r#"desc:"a high" desc:"A high""#));
+        assert!(!matches("desc:WEB"));
+        assert!(!matches("state:Failed"));
+    }
+
+    #[test]
+    fn combines_terms() {
+        assert!(matches("name:apache | name:nginx"));
+        assert!(matches("NOT (name:apache | state:running)"));
+        assert!(!matches("name:nginx !state:failed"));
+        assert!(matches(""));
+    }
+
+    #[test]
+    fn keeps_the_name_positions_of_all_matching_terms() {
+        let query: FilterQuery = "ngi name:gin".parse().unwrap();
+        let found = query.find(&nginx(), FilterMode::Substring).unwrap();
+
+        assert_eq!(found.positions, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn needs_details_for_the_file_state_and_preset() {
+        let needs_details = |query: &str| query.parse::<FilterQuery>().unwrap().needs_details();
+
+        assert!(needs_details("state:failed enabled:yes"));
+        assert!(needs_details("!(name:a | preset:enabled)"));
+        assert!(!needs_details("state:failed name:nginx"));
+        assert!(!needs_details(""));
+    }
+}
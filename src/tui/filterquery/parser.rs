@@ -0,0 +1,271 @@
+use std::{
+    fmt::{self, Display},
+    iter::Peekable,
+    str::Chars,
+    vec::IntoIter,
+};
+
+use super::{Expr, Term};
+
+enum Token {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    Term(Term),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+            Self::Or => write!(f, "OR"),
+            Self::And => write!(f, "AND"),
+            Self::Not => write!(f, "NOT"),
+            Self::Term(Term::Word(word)) => write!(f, "{word}"),
+            Self::Term(Term::Field(field, value)) => write!(f, "{field}:{value}"),
+        }
+    }
+}
+
+fn is_delimiter(ch: char) -> bool {
+    ch.is_whitespace() || matches!(ch, '(' | ')' | '|' | '&')
+}
+
+/// Reads a term up to the next delimiter outside of quotes. The part before the first
+/// unquoted `:` names a field.
+fn term(chars: &mut Peekable<Chars>) -> Result<Token, String> {
+    let mut text = String::new();
+    let mut field = None;
+    let mut quoted = false;
+    let mut was_quoted = false;
+
+    while let Some(ch) = chars.next_if(|ch| quoted || !is_delimiter(*ch)) {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            ':' if !quoted && field.is_none() => field = Some(std::mem::take(&mut text)),
+            _ => text.push(ch),
+        }
+    }
+
+    if quoted {
+        return Err(String::from("missing closing '\"'"));
+    }
+
+    match (field, text.as_str()) {
+        (Some(field), "") => Err(format!("missing value after '{field}:'")),
+        (Some(field), _) => Ok(Token::Term(Term::Field(field.parse()?, text))),
+        (None, "") => Err(String::from("missing text between the quotes")),
+        (None, "OR") if !was_quoted => Ok(Token::Or),
+        (None, "AND") if !was_quoted => Ok(Token::And),
+        (None, "NOT") if !was_quoted => Ok(Token::Not),
+        (None, _) => Ok(Token::Term(Term::Word(text))),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(ch) = chars.peek().copied() {
+        let token = match ch {
+            _ if ch.is_whitespace() => None,
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            '|' => Some(Token::Or),
+            '&' => Some(Token::And),
+            '!' => Some(Token::Not),
+            _ => {
+                tokens.push(term(&mut chars)?);
+                continue;
+            }
+        };
+
+        chars.next();
+        tokens.extend(token);
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Peekable<IntoIter<Token>>,
+    /// The last token read, for the error messages.
+    last: String,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.next();
+
+        if let Some(token) = &token {
+            self.last = token.to_string();
+        }
+
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.and()?];
+
+        while self
+            .tokens
+            .peek()
+            .is_some_and(|token| matches!(token, Token::Or))
+        {
+            self.next();
+            exprs.push(self.and()?);
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.unary()?];
+
+        loop {
+            match self.tokens.peek() {
+                None | Some(Token::Or | Token::Close) => break,
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(_) => {}
+            }
+
+            exprs.push(self.unary()?);
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Term(term)) => Ok(Expr::Term(term)),
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(String::from("missing ')'")),
+                }
+            }
+            Some(token) => Err(format!("missing term before '{token}'")),
+            None if self.last.is_empty() => Err(String::from("missing term")),
+            None => Err(format!("missing term after '{}'", self.last)),
+        }
+    }
+}
+
+/// Parses `query`, `None` when it has no terms at all.
+pub fn parse(query: &str) -> Result<Option<Expr>, String> {
+    let tokens = tokenize(query)?;
+
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser {
+        tokens: tokens.into_iter().peekable(),
+        last: String::new(),
+    };
+    let expr = parser.or()?;
+
+    match parser.next() {
+        None => Ok(Some(expr)),
+        Some(token) => Err(format!("unexpected '{token}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The expression with its grouping made explicit, e.g. `(a & (b | !c))`.
+    fn show(expr: &Expr) -> String {
+        let join = |exprs: &[Expr], separator: &str| {
+            let exprs: Vec<String> = exprs.iter().map(show).collect();
+
+            format!("({})", exprs.join(separator))
+        };
+
+        match expr {
+            Expr::Term(term) => Token::Term(match term {
+                Term::Word(word) => Term::Word(word.clone()),
+                Term::Field(field, value) => Term::Field(*field, value.clone()),
+            })
+            .to_string(),
+            Expr::Not(expr) => format!("!{}", show(expr)),
+            Expr::And(exprs) => join(exprs, " & "),
+            Expr::Or(exprs) => join(exprs, " | "),
+        }
+    }
+
+    fn parsed(query: &str) -> String {
+        parse(query).unwrap().as_ref().map(show).unwrap_or_default()
+    }
+
+    fn error(query: &str) -> String {
+        parse(query).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_nothing_from_blank_queries() {
+        assert!(parse("").unwrap().is_none());
+        assert!(parse("  \t").unwrap().is_none());
+    }
+
+    #[test]
+    fn parses_terms_next_to_each_other_as_and() {
+        assert_eq!(
+            parsed("state:failed name:nginx enabled:yes !desc:timer"),
+            "(state:failed & name:nginx & enabled:yes & !desc:timer)"
+        );
+        assert_eq!(parsed("a AND b&c"), "(a & b & c)");
+    }
+
+    #[test]
+    fn binds_and_tighter_than_or() {
+        assert_eq!(parsed("a b | c"), "((a & b) | c)");
+        assert_eq!(parsed("a OR b c"), "(a | (b & c))");
+        assert_eq!(parsed("a (b | c)"), "(a & (b | c))");
+        assert_eq!(parsed("NOT (a|b)"), "!(a | b)");
+        assert_eq!(parsed("!!a"), "!!a");
+    }
+
+    #[test]
+    fn reads_fields_and_quotes() {
+        assert_eq!(parsed(r#"desc:"web server""#), "desc:web server");
+        assert_eq!(parsed("description:web"), "desc:web");
+        assert_eq!(parsed("name:a:b"), "name:a:b");
+        assert_eq!(parsed(r#""OR" "a|b""#), "(OR & a|b)");
+        assert_eq!(parsed("or"), "or");
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert_eq!(error("state:"), "missing value after 'state:'");
+        assert!(error("status:failed").starts_with("unknown field 'status', try name, desc"));
+        assert_eq!(error(r#"desc:"web"#), "missing closing '\"'");
+        assert_eq!(error(r#""""#), "missing text between the quotes");
+        assert_eq!(error("(a"), "missing ')'");
+        assert_eq!(error("a)"), "unexpected ')'");
+        assert_eq!(error("()"), "missing term before ')'");
+        assert_eq!(error("| a"), "missing term before 'OR'");
+        assert_eq!(error("a |"), "missing term after 'OR'");
+        assert_eq!(error("!"), "missing term after 'NOT'");
+    }
+}
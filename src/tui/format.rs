@@ -0,0 +1,71 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use jiff::{Timestamp, tz::TimeZone};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Formats a byte count the way systemd does, e.g. `12.5M`.
+pub fn format_bytes(bytes: u64) -> String {
+    let mut unit_size: u64 = 1;
+
+    for suffix in ["B", "K", "M", "G", "T"] {
+        let next_unit_size = unit_size.saturating_mul(1024);
+
+        if bytes < next_unit_size || suffix == "T" {
+            let whole = bytes.checked_div(unit_size).unwrap_or_default();
+            let tenths = bytes
+                .checked_rem(unit_size)
+                .unwrap_or_default()
+                .saturating_mul(10)
+                .checked_div(unit_size)
+                .unwrap_or_default();
+
+            return if suffix == "B" {
+                format!("{whole}{suffix}")
+            } else {
+                format!("{whole}.{tenths}{suffix}")
+            };
+        }
+
+        unit_size = next_unit_size;
+    }
+
+    format!("{bytes}B")
+}
+
+/// Formats seconds since the epoch as a date and time in the local time zone, as systemctl
+/// shows them. Falls back to UTC when the zone is not known.
+pub fn format_date(seconds: u64) -> String {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| Timestamp::from_second(seconds).ok())
+        .map(|timestamp| {
+            timestamp
+                .to_zoned(TimeZone::system())
+                .strftime("%Y-%m-%d %H:%M:%S %Z")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Rough age of a timestamp, e.g. `2h 5min ago`.
+// clippy::arithmetic_side_effects, clippy::integer_division: the divisors are non-zero constants and
+// truncating is exactly what is wanted here.
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+pub fn format_age(seconds: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let age = now.saturating_sub(seconds);
+
+    match age {
+        0..60 => format!("{age}s ago"),
+        60..3600 => format!("{}min ago", age / 60),
+        3600..SECONDS_PER_DAY => format!("{}h {}min ago", age / 3600, age % 3600 / 60),
+        _ => format!(
+            "{} days {}h ago",
+            age / SECONDS_PER_DAY,
+            age % SECONDS_PER_DAY / 3600
+        ),
+    }
+}
@@ -0,0 +1,308 @@
+mod action;
+
+use super::{Mode, QuickFilter};
+use crate::systemd::{OperationType, Signal};
+pub use action::Action;
+
+const NORMAL_KEYS: &[(Action, &[char])] = &[
+    (Action::Filter, &['i', 'a', 'I', 'A']),
+    (Action::Search, &['/']),
+    (Action::SearchNext, &['n']),
+    (Action::SearchPrev, &['N']),
+    (Action::Mark, &['m']),
+    (Action::MarkRange, &['v']),
+    (Action::MarkAll, &['M']),
+    (Action::Refresh, &['R']),
+    (Action::Details, &['q']),
+    (Action::Logs, &['l']),
+    (Action::Operation(OperationType::Status), &['Q']),
+    (Action::Operation(OperationType::Start), &['w']),
+    (Action::Operation(OperationType::Stop), &['e']),
+    (Action::Operation(OperationType::Reload), &['r']),
+    (Action::Operation(OperationType::Restart), &['t']),
+    (Action::Operation(OperationType::Enable), &['y']),
+    (Action::Operation(OperationType::Disable), &['u']),
+    (Action::Operation(OperationType::EnableNow), &['Y']),
+    (Action::Operation(OperationType::DisableNow), &['U']),
+    (Action::Operation(OperationType::ReloadOrRestart), &['T']),
+    (Action::Operation(OperationType::TryRestart), &['b']),
+    (Action::Operation(OperationType::Mask), &['z']),
+    (Action::Operation(OperationType::Unmask), &['Z']),
+    (Action::Operation(OperationType::Kill(Signal::Term)), &['K']),
+    (Action::Operation(OperationType::ResetFailed), &['X']),
+    (Action::Operation(OperationType::DaemonReload), &['D']),
+    (Action::QuickFilter(QuickFilter::Failed), &['F']),
+    (Action::QuickFilter(QuickFilter::Running), &['W']),
+    (Action::QuickFilter(QuickFilter::EnabledInactive), &['E']),
+    (Action::QuickFilter(QuickFilter::Masked), &['x']),
+    (Action::Sort, &['S']),
+    (Action::ReverseSort, &['O']),
+    (Action::ToggleTarget, &['f']),
+    (Action::ToggleScope, &['s']),
+    (Action::NextUnitType, &['c']),
+    (Action::Down, &['j']),
+    (Action::Up, &['k']),
+    (Action::ShowKeys, &['o']),
+    (Action::Help, &['p']),
+];
+
+const LOG_KEYS: &[(Action, &[char])] = &[
+    (Action::Follow, &['F']),
+    (Action::Priority, &['P']),
+    (Action::TimeRange, &['T']),
+    (Action::Top, &['g']),
+    (Action::Bottom, &['G']),
+    (Action::Search, &['/']),
+    (Action::SearchNext, &['n']),
+    (Action::SearchPrev, &['N']),
+    (Action::Down, &['j']),
+    (Action::Up, &['k']),
+    (Action::Help, &['p', 'o']),
+    (Action::Close, &['l']),
+];
+
+#[derive(Clone)]
+struct Binding {
+    action: Action,
+    keys: Vec<char>,
+    /// Line of the configuration file the keys were set on, 0 for the defaults.
+    line: usize,
+}
+
+fn bindings(defaults: &[(Action, &[char])]) -> Vec<Binding> {
+    defaults
+        .iter()
+        .map(|(action, keys)| Binding {
+            action: *action,
+            keys: keys.to_vec(),
+            line: 0,
+        })
+        .collect()
+}
+
+fn key_name(key: char) -> String {
+    match key {
+        ' ' => String::from("space"),
+        '\t' => String::from("tab"),
+        key => key.to_string(),
+    }
+}
+
+/// Maps the keys pressed in normal and log mode to actions. The other modes edit text,
+/// so their keys are not remappable.
+#[derive(Clone)]
+pub struct Keymap {
+    normal: Vec<Binding>,
+    log: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            normal: bindings(NORMAL_KEYS),
+            log: bindings(LOG_KEYS),
+        }
+    }
+}
+
+impl Keymap {
+    fn bindings(&self, mode: Mode) -> &[Binding] {
+        match mode {
+            Mode::Normal => &self.normal,
+            Mode::Log => &self.log,
+            _ => &[],
+        }
+    }
+
+    pub fn action(&self, mode: Mode, key: char) -> Option<Action> {
+        self.bindings(mode)
+            .iter()
+            .find(|binding| binding.keys.contains(&key))
+            .map(|binding| binding.action)
+    }
+
+    /// The keys bound to `action`, separated by slashes, e.g. `i/a/I/A`.
+    pub fn keys(&self, mode: Mode, action: Action) -> String {
+        self.bindings(mode)
+            .iter()
+            .find(|binding| binding.action == action)
+            .map(|binding| {
+                binding
+                    .keys
+                    .iter()
+                    .map(|key| key_name(*key))
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_default()
+    }
+
+    /// `label: keys` of each action, separated by bars. Actions without keys are left out.
+    pub fn help(&self, mode: Mode, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let keys = self.keys(mode, *action);
+
+                (!keys.is_empty()).then(|| format!("{}: {keys}", action.label()))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// Binds `keys` to the action named `action` in the mode named `mode`, replacing the
+    /// keys it had. Hyphens in the name stand for underscores, as in the operation names.
+    /// `line` is where the binding comes from, to report conflicts.
+    pub fn bind(
+        &mut self,
+        mode: &str,
+        action: &str,
+        keys: &[String],
+        line: usize,
+    ) -> Result<(), String> {
+        let bindings = match mode {
+            "normal" => &mut self.normal,
+            "log" => &mut self.log,
+            _ => {
+                return Err(format!(
+                    "Unknown key section 'keys.{mode}', expected keys.normal or keys.log"
+                ));
+            }
+        };
+
+        let name = action.replace('-', "_");
+        let binding = bindings
+            .iter_mut()
+            .find(|binding| binding.action.to_string() == name)
+            .ok_or_else(|| format!("Unknown action '{action}' in {mode} mode"))?;
+
+        let mut chars = Vec::new();
+
+        for key in keys {
+            let mut key_chars = key.chars();
+
+            let (Some(key), None) = (key_chars.next(), key_chars.next()) else {
+                return Err(format!("'{key}' is not a single key"));
+            };
+
+            if mode == "normal" && key.is_ascii_digit() {
+                return Err(format!(
+                    "'{key}' can not be bound, digits are counts in normal mode"
+                ));
+            }
+
+            chars.push(key);
+        }
+
+        binding.keys = chars;
+        binding.line = line;
+
+        Ok(())
+    }
+
+    /// Fails on the first key bound to two actions of the same mode, with the line of the
+    /// binding that came last.
+    pub fn check(&self) -> Result<(), (usize, String)> {
+        for (mode, bindings) in [("normal", &self.normal), ("log", &self.log)] {
+            for (idx, binding) in bindings.iter().enumerate() {
+                for other in bindings.iter().skip(idx.saturating_add(1)) {
+                    if let Some(key) = binding.keys.iter().find(|key| other.keys.contains(key)) {
+                        return Err((
+                            binding.line.max(other.line),
+                            format!(
+                                "'{}' is bound to both {} and {} in {mode} mode",
+                                key_name(*key),
+                                binding.action,
+                                other.action
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keys_do_not_conflict() {
+        assert!(Keymap::default().check().is_ok());
+    }
+
+    #[test]
+    fn binds_the_readme_example() {
+        let mut keymap = Keymap::default();
+
+        keymap
+            .bind("normal", "start", &[String::from("g")], 2)
+            .unwrap();
+        keymap
+            .bind(
+                "normal",
+                "show_keys",
+                &[String::from("o"), String::from("?")],
+                3,
+            )
+            .unwrap();
+        keymap
+            .bind("log", "close", &[String::from("l"), String::from("q")], 6)
+            .unwrap();
+
+        assert!(keymap.check().is_ok());
+        assert!(keymap.action(Mode::Normal, 'g') == Some(Action::Operation(OperationType::Start)));
+    }
+
+    #[test]
+    fn names_actions_with_underscores_and_accepts_hyphens() {
+        let mut keymap = Keymap::default();
+
+        keymap
+            .bind("normal", "enable-now", &[String::from("g")], 2)
+            .unwrap();
+        keymap
+            .bind("normal", "only-failed", &[String::from("G")], 3)
+            .unwrap();
+
+        assert_eq!(
+            keymap.keys(Mode::Normal, Action::QuickFilter(QuickFilter::Failed)),
+            "G"
+        );
+        assert_eq!(
+            Action::Operation(OperationType::EnableNow).to_string(),
+            "enable_now"
+        );
+        assert_eq!(
+            Action::Operation(OperationType::EnableNow).label(),
+            "enable-now"
+        );
+        assert!(
+            keymap.action(Mode::Normal, 'g') == Some(Action::Operation(OperationType::EnableNow))
+        );
+
+        for (action, _) in NORMAL_KEYS.iter().chain(LOG_KEYS) {
+            assert!(!action.to_string().contains('-'));
+        }
+    }
+
+    #[test]
+    fn reports_the_line_of_a_conflict() {
+        let mut keymap = Keymap::default();
+
+        keymap
+            .bind("normal", "start", &[String::from("S")], 2)
+            .unwrap();
+
+        assert_eq!(
+            keymap.check(),
+            Err((
+                2,
+                String::from("'S' is bound to both start and sort in normal mode")
+            ))
+        );
+    }
+}
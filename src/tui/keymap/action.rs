@@ -0,0 +1,100 @@
+use std::fmt::{self, Display};
+
+use super::super::QuickFilter;
+use crate::systemd::OperationType;
+
+/// Something a key can be bound to. Its name is how the configuration file refers to it, with
+/// words separated by underscores.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Up,
+    Down,
+    Top,
+    Bottom,
+    Filter,
+    Search,
+    SearchNext,
+    SearchPrev,
+    Mark,
+    MarkRange,
+    MarkAll,
+    Refresh,
+    ToggleTarget,
+    ToggleScope,
+    NextUnitType,
+    Details,
+    Logs,
+    Follow,
+    Priority,
+    TimeRange,
+    Operation(OperationType),
+    QuickFilter(QuickFilter),
+    Sort,
+    ReverseSort,
+    ShowKeys,
+    Help,
+    Close,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Up => write!(f, "up"),
+            Self::Down => write!(f, "down"),
+            Self::Top => write!(f, "top"),
+            Self::Bottom => write!(f, "bottom"),
+            Self::Filter => write!(f, "filter"),
+            Self::Search => write!(f, "search"),
+            Self::SearchNext => write!(f, "search_next"),
+            Self::SearchPrev => write!(f, "search_prev"),
+            Self::Mark => write!(f, "mark"),
+            Self::MarkRange => write!(f, "mark_range"),
+            Self::MarkAll => write!(f, "mark_all"),
+            Self::Refresh => write!(f, "refresh"),
+            Self::ToggleTarget => write!(f, "toggle_target"),
+            Self::ToggleScope => write!(f, "toggle_scope"),
+            Self::NextUnitType => write!(f, "unit_type"),
+            Self::Details => write!(f, "details"),
+            Self::Logs => write!(f, "logs"),
+            Self::Follow => write!(f, "follow"),
+            Self::Priority => write!(f, "priority"),
+            Self::TimeRange => write!(f, "time_range"),
+            Self::Operation(operation_type) => {
+                write!(f, "{}", operation_type.to_string().replace('-', "_"))
+            }
+            Self::QuickFilter(filter) => write!(f, "only_{}", filter.to_string().replace('-', "_")),
+            Self::Sort => write!(f, "sort"),
+            Self::ReverseSort => write!(f, "reverse_sort"),
+            Self::ShowKeys => write!(f, "show_keys"),
+            Self::Help => write!(f, "help"),
+            Self::Close => write!(f, "close"),
+        }
+    }
+}
+
+impl Action {
+    /// How the action is described in the help messages.
+    pub fn label(self) -> String {
+        let label = match self {
+            Self::Filter => "filter mode",
+            Self::Search => "search mode",
+            Self::SearchNext => "search next",
+            Self::SearchPrev => "search prev",
+            Self::MarkRange => "mark range",
+            Self::MarkAll => "mark all",
+            Self::ToggleTarget => "alternate loaded/all",
+            Self::ToggleScope => "system/user",
+            Self::NextUnitType => "unit type",
+            Self::TimeRange => "time range",
+            Self::Sort => "sort by",
+            Self::ReverseSort => "reverse sort",
+            Self::ShowKeys => "show keys",
+            Self::Close => "back",
+            Self::Operation(operation_type) => return operation_type.to_string(),
+            Self::QuickFilter(filter) => return format!("only {filter}"),
+            action => return action.to_string(),
+        };
+
+        label.to_string()
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::systemd::Service;
+
+const ALL: [QuickFilter; 4] = [
+    QuickFilter::Failed,
+    QuickFilter::Running,
+    QuickFilter::EnabledInactive,
+    QuickFilter::Masked,
+];
+
+/// Units shown by a single key, on top of the text filter.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum QuickFilter {
+    Failed,
+    Running,
+    /// Enabled units that are not running, e.g. because they failed or were stopped.
+    EnabledInactive,
+    Masked,
+}
+
+impl Display for QuickFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed => write!(f, "failed"),
+            Self::Running => write!(f, "running"),
+            Self::EnabledInactive => write!(f, "enabled-inactive"),
+            Self::Masked => write!(f, "masked"),
+        }
+    }
+}
+
+impl FromStr for QuickFilter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        ALL.into_iter()
+            .find(|filter| filter.to_string() == value)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown quick filter '{value}', expected one of: failed, running, enabled-inactive, masked"
+                )
+            })
+    }
+}
+
+impl QuickFilter {
+    /// Whether the filter looks at the unit file state, which `Backend::fill_details` has to
+    /// fill in for loaded units.
+    pub fn needs_details(self) -> bool {
+        self == Self::EnabledInactive
+    }
+
+    pub fn matches(self, service: &Service) -> bool {
+        match self {
+            Self::Failed => service.active_state == "failed",
+            Self::Running => service.sub_state == "running",
+            Self::EnabledInactive => {
+                service.unit_file_state.starts_with("enabled")
+                    && matches!(service.active_state.as_str(), "inactive" | "failed")
+            }
+            Self::Masked => {
+                service.load_state == "masked" || service.unit_file_state.starts_with("masked")
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use regex::{Regex, RegexBuilder};
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::prelude::*;
+
+/// How the search query is turned into a pattern.
+#[derive(Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// The query is a regular expression instead of literal text.
+    pub regex: bool,
+    /// Case always matters. Otherwise it only does when the query has capitals.
+    pub match_case: bool,
+    /// Matches have to start and end at word boundaries.
+    pub whole_word: bool,
+}
+
+impl Display for SearchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.regex { "regex" } else { "literal" };
+        let case = if self.match_case {
+            "match case"
+        } else {
+            "smart case"
+        };
+        let word = if self.whole_word { ", whole word" } else { "" };
+
+        write!(f, "{kind}, {case}{word}")
+    }
+}
+
+/// Whether the query has capitals, leaving out the letters of escapes like `\S` in regexes.
+fn has_capitals(query: &str, regex: bool) -> bool {
+    let mut escaped = false;
+
+    query.chars().any(|ch| {
+        let is_capital = !escaped && ch.is_uppercase();
+        escaped = regex && !escaped && ch == '\\';
+
+        is_capital
+    })
+}
+
+/// A compiled search query.
+#[derive(Clone)]
+pub struct SearchPattern {
+    regex: Regex,
+}
+
+impl SearchPattern {
+    /// Compiles `query`, failing with a short description when it is not a valid regex.
+    pub fn new(query: &str, options: SearchOptions) -> Result<Self, String> {
+        let mut pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+
+        if options.whole_word {
+            pattern = format!(r"\b(?:{pattern})\b");
+        }
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!options.match_case && !has_capitals(query, options.regex))
+            .build()
+            .map(|regex| Self { regex })
+            .map_err(|err| {
+                // Syntax errors point at the pattern over several lines, the last one says why.
+                let err = err.to_string();
+                let reason = err.lines().last().unwrap_or_default();
+
+                reason.strip_prefix("error: ").unwrap_or(reason).to_string()
+            })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+
+    /// Byte ranges of the matches in `text`. Empty matches, e.g. of `a*`, are left out.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Range<ByteIdx>> + 'a {
+        self.regex
+            .find_iter(text)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(regex: bool, match_case: bool, whole_word: bool) -> SearchOptions {
+        SearchOptions {
+            regex,
+            match_case,
+            whole_word,
+        }
+    }
+
+    /// Start and end of the matches.
+    fn matches(query: &str, options: SearchOptions, text: &str) -> Vec<(ByteIdx, ByteIdx)> {
+        SearchPattern::new(query, options)
+            .unwrap()
+            .find_iter(text)
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn searches_for_literal_text_by_default() {
+        let literal = SearchOptions::default();
+
+        assert_eq!(matches("a.b", literal, "axb a.b"), [(4, 7)]);
+        assert_eq!(matches("(", literal, "f(x)"), [(1, 2)]);
+        assert_eq!(matches("a.b", options(true, false, false), "axb"), [(0, 3)]);
+    }
+
+    #[test]
+    fn reports_why_a_regex_is_invalid() {
+        assert_eq!(
+            SearchPattern::new("(", options(true, false, false)).err(),
+            Some(String::from("unclosed group"))
+        );
+    }
+
+    #[test]
+    fn leaves_escapes_out_of_the_capitals() {
+        assert!(!has_capitals(r"\S+", true));
+        assert!(has_capitals(r"\S+", false));
+        assert!(has_capitals(r"\\Ssh", true));
+        assert!(!has_capitals("ssh", false));
+
+        assert_eq!(
+            matches(r"\Sssh", options(true, false, false), " xSSH"),
+            [(1, 5)]
+        );
+        assert!(matches("Ssh", SearchOptions::default(), "ssh").is_empty());
+        assert!(matches("ssh", options(false, true, false), "SSH").is_empty());
+    }
+
+    #[test]
+    fn matches_whole_words() {
+        let whole_word = options(false, false, true);
+
+        assert_eq!(
+            matches("ssh", whole_word, "ssh sshd ssh.service"),
+            [(0, 3), (9, 12)]
+        );
+        assert_eq!(
+            matches("cron|ssh", options(true, false, true), "sshd cron"),
+            [(5, 9)]
+        );
+    }
+
+    #[test]
+    fn drops_empty_matches() {
+        let regex = options(true, false, false);
+        let pattern = SearchPattern::new("a*", regex).unwrap();
+
+        assert_eq!(matches("a*", regex, "baab"), [(1, 3)]);
+        assert!(!pattern.is_match("bcd"));
+    }
+}
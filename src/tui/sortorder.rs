@@ -0,0 +1,302 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use super::format::{format_bytes, format_date};
+use crate::systemd::{Service, Target};
+
+const ALL: [SortKey; 8] = [
+    SortKey::Listing,
+    SortKey::Name,
+    SortKey::Active,
+    SortKey::Sub,
+    SortKey::Load,
+    SortKey::FileState,
+    SortKey::Memory,
+    SortKey::ActiveSince,
+];
+
+/// What the units are sorted by.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum SortKey {
+    /// The order the backend listed the units in.
+    #[default]
+    Listing,
+    Name,
+    Active,
+    Sub,
+    Load,
+    FileState,
+    Memory,
+    ActiveSince,
+}
+
+#[derive(Eq, Ord, PartialEq, PartialOrd)]
+enum SortValue<'a> {
+    Text(&'a str),
+    Number(u64),
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Listing => write!(f, "listing"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::Sub => write!(f, "sub"),
+            Self::Load => write!(f, "load"),
+            Self::FileState => write!(f, "file-state"),
+            Self::Memory => write!(f, "memory"),
+            Self::ActiveSince => write!(f, "active-since"),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        ALL.into_iter()
+            .find(|key| key.to_string() == value)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown sort key '{value}', expected one of: {}",
+                    ALL.map(|key| key.to_string()).join(", ")
+                )
+            })
+    }
+}
+
+impl SortKey {
+    /// Whether `target` lists the field the key sorts by. Unit files have no runtime state.
+    fn applies_to(self, target: Target) -> bool {
+        target == Target::Memory || matches!(self, Self::Listing | Self::Name | Self::FileState)
+    }
+
+    /// The next key that applies to `target`, back to the listing order after the last one.
+    pub fn next(self, target: Target) -> Self {
+        ALL.into_iter()
+            .cycle()
+            .skip_while(|key| *key != self)
+            .skip(1)
+            .find(|key| key.applies_to(target))
+            .unwrap_or_default()
+    }
+
+    /// Whether the field has to be filled in by `Backend::fill_details` for loaded units.
+    pub fn needs_details(self) -> bool {
+        matches!(self, Self::FileState | Self::Memory | Self::ActiveSince)
+    }
+
+    /// The field of `service` the key sorts by, for the column added to the listing when
+    /// `target` does not show it already. Empty when it is not known.
+    pub fn column(self, service: &Service, target: Target) -> Option<String> {
+        if target != Target::Memory || !self.needs_details() {
+            return None;
+        }
+
+        Some(match self {
+            Self::Memory => service.memory.map(format_bytes).unwrap_or_default(),
+            Self::ActiveSince => service.active_since.map(format_date).unwrap_or_default(),
+            _ => service.unit_file_state.clone(),
+        })
+    }
+
+    /// The field of `service` the key sorts by, `None` when it is not known.
+    fn value(self, service: &Service) -> Option<SortValue<'_>> {
+        let text = match self {
+            Self::Listing => return None,
+            Self::Memory => return service.memory.map(SortValue::Number),
+            Self::ActiveSince => return service.active_since.map(SortValue::Number),
+            Self::Name => &service.name,
+            Self::Active => &service.active_state,
+            Self::Sub => &service.sub_state,
+            Self::Load => &service.load_state,
+            Self::FileState => &service.unit_file_state,
+        };
+
+        (!text.is_empty()).then_some(SortValue::Text(text))
+    }
+}
+
+/// A key and a direction to sort the units in.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.descending { "desc" } else { "asc" };
+
+        write!(f, "{} {direction}", self.key)
+    }
+}
+
+impl SortOrder {
+    /// Whether units are shown as the backend listed them.
+    pub fn is_default(self) -> bool {
+        self == Self::default()
+    }
+
+    /// Sorts `services` by the key, by name where it is the same. Units whose field is not
+    /// known come last either way.
+    pub fn sort(self, services: &mut [Service]) {
+        if self.key == SortKey::Listing {
+            if self.descending {
+                services.reverse();
+            }
+
+            return;
+        }
+
+        services.sort_by(|a, b| {
+            let ordering = match (self.key.value(a), self.key.value(b)) {
+                (Some(a), Some(b)) if self.descending => b.cmp(&a),
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+
+            ordering.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, active_state: &str, memory: Option<u64>) -> Service {
+        Service {
+            name: name.to_string(),
+            active_state: active_state.to_string(),
+            memory,
+            ..Service::default()
+        }
+    }
+
+    fn sorted(key: SortKey, descending: bool) -> Vec<String> {
+        let mut services = vec![
+            service("nginx.service", "failed", None),
+            service("cron.service", "active", Some(2048)),
+            service("apache.service", "", Some(1024)),
+            service("ssh.service", "active", Some(4096)),
+        ];
+
+        SortOrder { key, descending }.sort(&mut services);
+
+        services.into_iter().map(|service| service.name).collect()
+    }
+
+    #[test]
+    fn cycles_through_the_keys_of_the_target() {
+        let cycle = |target: Target| {
+            let mut keys = vec![SortKey::Listing];
+            let mut key = SortKey::Listing.next(target);
+
+            while key != SortKey::Listing {
+                keys.push(key);
+                key = key.next(target);
+            }
+
+            keys.iter().map(ToString::to_string).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            cycle(Target::Memory),
+            [
+                "listing",
+                "name",
+                "active",
+                "sub",
+                "load",
+                "file-state",
+                "memory",
+                "active-since"
+            ]
+        );
+        assert_eq!(cycle(Target::Files), ["listing", "name", "file-state"]);
+        // A key picked for loaded units moves on to one that applies to unit files.
+        assert!(SortKey::Memory.next(Target::Files) == SortKey::Listing);
+        assert!(SortKey::Active.next(Target::Files) == SortKey::FileState);
+    }
+
+    #[test]
+    fn keeps_or_reverses_the_listing_order() {
+        assert_eq!(
+            sorted(SortKey::Listing, false),
+            [
+                "nginx.service",
+                "cron.service",
+                "apache.service",
+                "ssh.service"
+            ]
+        );
+        assert_eq!(
+            sorted(SortKey::Listing, true),
+            [
+                "ssh.service",
+                "apache.service",
+                "cron.service",
+                "nginx.service"
+            ]
+        );
+    }
+
+    #[test]
+    fn sorts_by_name_where_the_field_is_the_same() {
+        assert_eq!(
+            sorted(SortKey::Active, false),
+            [
+                "cron.service",
+                "ssh.service",
+                "nginx.service",
+                "apache.service"
+            ]
+        );
+        assert_eq!(
+            sorted(SortKey::Active, true),
+            [
+                "nginx.service",
+                "cron.service",
+                "ssh.service",
+                "apache.service"
+            ]
+        );
+    }
+
+    #[test]
+    fn puts_unknown_values_last_either_way() {
+        assert_eq!(
+            sorted(SortKey::Memory, false),
+            [
+                "apache.service",
+                "cron.service",
+                "ssh.service",
+                "nginx.service"
+            ]
+        );
+        assert_eq!(
+            sorted(SortKey::Memory, true),
+            [
+                "ssh.service",
+                "cron.service",
+                "apache.service",
+                "nginx.service"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_the_key_names() {
+        assert!("active-since".parse::<SortKey>().unwrap() == SortKey::ActiveSince);
+        assert!("size".parse::<SortKey>().err().is_some_and(|err| {
+            err.starts_with("Unknown sort key 'size', expected one of: listing, name")
+        }));
+    }
+}
@@ -4,23 +4,34 @@ use crossterm::{
     queue,
     style::{
         Attribute::{Reset, Reverse},
-        Print, ResetColor, SetBackgroundColor, SetForegroundColor,
+        Print, SetAttribute, SetAttributes, SetBackgroundColor, SetForegroundColor,
     },
     terminal::{
         Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
         LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode, size,
     },
 };
-use std::io::{Error, Write, stdout};
-
-mod attribute;
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Error, IsTerminal, Write, stdout},
+    sync::{Mutex, OnceLock},
+};
 
-use super::AnnotatedString;
+use super::{
+    AnnotatedString,
+    theme::{Attribute, Theme},
+};
 use crate::prelude::*;
-use attribute::Attribute;
 
 pub struct Terminal;
 
+/// The controlling terminal, used instead of stdout when stdout is redirected, as in
+/// `unit=$(sm pick)`, so that only the picked units end up in stdout.
+static TTY: OnceLock<Option<Mutex<BufWriter<File>>>> = OnceLock::new();
+
+/// Colors of the annotated rows, the default theme unless one was set before drawing.
+static THEME: OnceLock<Theme> = OnceLock::new();
+
 /// Represents the Terminal.
 /// Edge Case for platforms where `usize` < `u16`:
 /// Regardless of the actual size of the Terminal, this representation
@@ -45,9 +56,35 @@ impl Terminal {
         Ok(Size { height, width })
     }
 
+    fn with_output<T>(write: impl FnOnce(&mut dyn Write) -> Result<T, Error>) -> Result<T, Error> {
+        let tty = TTY.get_or_init(|| {
+            if stdout().is_terminal() {
+                return None;
+            }
+
+            OpenOptions::new()
+                .write(true)
+                .open("/dev/tty")
+                .ok()
+                .map(|file| Mutex::new(BufWriter::new(file)))
+        });
+
+        if let Some(tty) = tty {
+            let mut tty = tty
+                .lock()
+                .map_err(|_err| Error::other("The terminal is not usable anymore"))?;
+
+            write(&mut *tty)
+        } else {
+            write(&mut stdout())
+        }
+    }
+
     fn queue_command<T: Command>(command: T) -> Result<(), Error> {
-        queue!(stdout(), command)?;
-        Ok(())
+        Self::with_output(|mut output| {
+            queue!(&mut output, command)?;
+            Ok(())
+        })
     }
 
     pub fn move_caret_to(row: RowIdx, column: Option<ColIdx>) -> Result<(), Error> {
@@ -88,7 +125,16 @@ impl Terminal {
         Self::print_row(row, &format!("{Reverse}{line_text:width$.width$}{Reset}"))
     }
 
+    /// Sets the theme annotated rows are drawn with. Only the first call has an effect.
+    pub fn set_theme(theme: Theme) {
+        let _ = THEME.set(theme);
+    }
+
     fn set_attribute(attribute: &Attribute) -> Result<(), Error> {
+        if !attribute.effects.is_empty() {
+            Self::queue_command(SetAttributes(attribute.effects))?;
+        }
+
         if let Some(foreground_color) = attribute.foreground {
             Self::queue_command(SetForegroundColor(foreground_color))?;
         }
@@ -100,8 +146,8 @@ impl Terminal {
         Ok(())
     }
 
-    fn reset_color() -> Result<(), Error> {
-        Self::queue_command(ResetColor)
+    fn reset_attribute() -> Result<(), Error> {
+        Self::queue_command(SetAttribute(Reset))
     }
 
     pub fn print_annotated_row(
@@ -114,19 +160,26 @@ impl Terminal {
         annotated_string
             .into_iter()
             .try_for_each(|part| -> Result<(), Error> {
-                if let Some(annotation_type) = part.annotation_type {
-                    let attribute: Attribute = annotation_type.into();
-
-                    Self::set_attribute(&attribute)?;
+                if part.annotation_types.is_empty() {
+                    return Self::print(part.string);
                 }
 
+                let theme = THEME.get_or_init(Theme::default);
+                let attribute = part.annotation_types.iter().fold(
+                    Attribute::default(),
+                    |attribute, annotation_type| {
+                        attribute.overlay(theme.attribute(*annotation_type))
+                    },
+                );
+
+                Self::set_attribute(&attribute)?;
                 Self::print(part.string)?;
-                Self::reset_color()
+                Self::reset_attribute()
             })
     }
 
     pub fn execute() -> Result<(), Error> {
-        stdout().flush()
+        Self::with_output(|output| output.flush())
     }
 
     fn enter_alternate_screen() -> Result<(), Error> {
@@ -166,4 +219,15 @@ impl Terminal {
 
         disable_raw_mode()
     }
+
+    /// Hands the terminal back to the shell so a child process can use it.
+    pub fn suspend() -> Result<(), Error> {
+        Self::terminate()
+    }
+
+    /// Takes the terminal over again after a `suspend`.
+    /// Every component has to be redrawn afterwards.
+    pub fn resume() -> Result<(), Error> {
+        Self::initialize()
+    }
 }
@@ -0,0 +1,340 @@
+use crossterm::style::{Attribute as Effect, Color};
+use std::collections::HashMap;
+
+mod attribute;
+mod colordepth;
+
+use super::AnnotationType;
+pub use attribute::Attribute;
+pub use colordepth::ColorDepth;
+
+/// Names the annotation types go by in the configuration file.
+const ANNOTATION_NAMES: [(&str, AnnotationType); 12] = [
+    ("match", AnnotationType::Match),
+    ("filter_match", AnnotationType::FilterMatch),
+    ("selected", AnnotationType::Selected),
+    ("selected_match", AnnotationType::SelectedMatch),
+    ("marked", AnnotationType::Marked),
+    ("selected_marked", AnnotationType::SelectedMarked),
+    ("changed", AnnotationType::Changed),
+    ("failed", AnnotationType::Failed),
+    ("active", AnnotationType::Active),
+    ("inactive", AnnotationType::Inactive),
+    ("activating", AnnotationType::Activating),
+    ("masked", AnnotationType::Masked),
+];
+
+const COLOR_NAMES: [(&str, Color); 16] = [
+    ("black", Color::Black),
+    ("dark_grey", Color::DarkGrey),
+    ("red", Color::Red),
+    ("dark_red", Color::DarkRed),
+    ("green", Color::Green),
+    ("dark_green", Color::DarkGreen),
+    ("yellow", Color::Yellow),
+    ("dark_yellow", Color::DarkYellow),
+    ("blue", Color::Blue),
+    ("dark_blue", Color::DarkBlue),
+    ("magenta", Color::Magenta),
+    ("dark_magenta", Color::DarkMagenta),
+    ("cyan", Color::Cyan),
+    ("dark_cyan", Color::DarkCyan),
+    ("white", Color::White),
+    ("grey", Color::Grey),
+];
+
+const EFFECT_NAMES: [(&str, Effect); 6] = [
+    ("bold", Effect::Bold),
+    ("dim", Effect::Dim),
+    ("italic", Effect::Italic),
+    ("underlined", Effect::Underlined),
+    ("reverse", Effect::Reverse),
+    ("crossed_out", Effect::CrossedOut),
+];
+
+const WHITE: Color = Color::Rgb {
+    r: 255,
+    g: 255,
+    b: 255,
+};
+
+/// The colors and effects each annotation type is drawn with.
+#[derive(Clone)]
+pub struct Theme {
+    attributes: HashMap<AnnotationType, Attribute>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub const NAMES: &str = "dark, light, high-contrast";
+
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    fn from_attributes(attributes: [(AnnotationType, Attribute); 12]) -> Self {
+        Self {
+            attributes: attributes.into_iter().collect(),
+        }
+    }
+
+    fn dark() -> Self {
+        Self::from_attributes([
+            (
+                AnnotationType::Match,
+                Attribute::background(150, 220, 130).with_foreground(WHITE),
+            ),
+            (
+                AnnotationType::FilterMatch,
+                Attribute::foreground(250, 190, 60).with_effect(Effect::Bold),
+            ),
+            (
+                AnnotationType::SelectedMatch,
+                Attribute::background(60, 150, 30).with_foreground(WHITE),
+            ),
+            (AnnotationType::Selected, Attribute::background(60, 55, 30)),
+            (AnnotationType::Marked, Attribute::background(35, 55, 95)),
+            (
+                AnnotationType::SelectedMarked,
+                Attribute::background(75, 85, 130),
+            ),
+            (AnnotationType::Changed, Attribute::background(95, 70, 20)),
+            (AnnotationType::Failed, Attribute::foreground(240, 90, 90)),
+            (AnnotationType::Active, Attribute::foreground(130, 210, 110)),
+            (AnnotationType::Inactive, Attribute::effect(Effect::Dim)),
+            (
+                AnnotationType::Activating,
+                Attribute::foreground(235, 200, 80),
+            ),
+            (
+                AnnotationType::Masked,
+                Attribute::effect(Effect::CrossedOut),
+            ),
+        ])
+    }
+
+    fn light() -> Self {
+        Self::from_attributes([
+            (
+                AnnotationType::Match,
+                Attribute::background(170, 230, 150).with_foreground(Color::Black),
+            ),
+            (
+                AnnotationType::FilterMatch,
+                Attribute::foreground(170, 90, 0).with_effect(Effect::Bold),
+            ),
+            (
+                AnnotationType::SelectedMatch,
+                Attribute::background(60, 150, 30).with_foreground(WHITE),
+            ),
+            (
+                AnnotationType::Selected,
+                Attribute::background(235, 225, 170),
+            ),
+            (AnnotationType::Marked, Attribute::background(200, 215, 245)),
+            (
+                AnnotationType::SelectedMarked,
+                Attribute::background(165, 185, 235),
+            ),
+            (
+                AnnotationType::Changed,
+                Attribute::background(250, 210, 140),
+            ),
+            (AnnotationType::Failed, Attribute::foreground(190, 30, 30)),
+            (AnnotationType::Active, Attribute::foreground(30, 130, 30)),
+            (AnnotationType::Inactive, Attribute::effect(Effect::Dim)),
+            (
+                AnnotationType::Activating,
+                Attribute::foreground(160, 110, 0),
+            ),
+            (
+                AnnotationType::Masked,
+                Attribute::effect(Effect::CrossedOut),
+            ),
+        ])
+    }
+
+    /// Only uses the basic colors, so it looks the same on every terminal.
+    fn high_contrast() -> Self {
+        let colors = |foreground, background| Attribute {
+            foreground: Some(foreground),
+            background: Some(background),
+            ..Attribute::default()
+        };
+
+        Self::from_attributes([
+            (
+                AnnotationType::Match,
+                colors(Color::Black, Color::Green).with_effect(Effect::Bold),
+            ),
+            (
+                AnnotationType::FilterMatch,
+                Attribute::effect(Effect::Bold).with_effect(Effect::Underlined),
+            ),
+            (
+                AnnotationType::SelectedMatch,
+                colors(Color::Black, Color::Yellow).with_effect(Effect::Bold),
+            ),
+            (AnnotationType::Selected, colors(Color::Black, Color::White)),
+            (AnnotationType::Marked, colors(Color::White, Color::Blue)),
+            (
+                AnnotationType::SelectedMarked,
+                colors(Color::Black, Color::Cyan),
+            ),
+            (
+                AnnotationType::Changed,
+                colors(Color::Black, Color::Magenta),
+            ),
+            (
+                AnnotationType::Failed,
+                Attribute::effect(Effect::Bold).with_foreground(Color::Red),
+            ),
+            (
+                AnnotationType::Active,
+                Attribute::default().with_foreground(Color::Green),
+            ),
+            (
+                AnnotationType::Inactive,
+                Attribute::default().with_foreground(Color::DarkGrey),
+            ),
+            (
+                AnnotationType::Activating,
+                Attribute::default().with_foreground(Color::Yellow),
+            ),
+            (
+                AnnotationType::Masked,
+                Attribute::effect(Effect::CrossedOut),
+            ),
+        ])
+    }
+
+    /// Tells the annotation types apart by effects alone, for `NO_COLOR` and dumb terminals.
+    fn monochrome() -> Self {
+        let effects = |effects: &[Effect]| Attribute {
+            effects: effects.into(),
+            ..Attribute::default()
+        };
+
+        Self::from_attributes([
+            (AnnotationType::Match, effects(&[Effect::Underlined])),
+            (
+                AnnotationType::FilterMatch,
+                effects(&[Effect::Bold, Effect::Underlined]),
+            ),
+            (
+                AnnotationType::SelectedMatch,
+                effects(&[Effect::Reverse, Effect::Underlined]),
+            ),
+            (AnnotationType::Selected, effects(&[Effect::Reverse])),
+            (AnnotationType::Marked, effects(&[Effect::Bold])),
+            (
+                AnnotationType::SelectedMarked,
+                effects(&[Effect::Reverse, Effect::Bold]),
+            ),
+            (AnnotationType::Changed, effects(&[Effect::Italic])),
+            (AnnotationType::Failed, effects(&[Effect::Underlined])),
+            (AnnotationType::Active, effects(&[])),
+            (AnnotationType::Inactive, effects(&[Effect::Dim])),
+            (AnnotationType::Activating, effects(&[])),
+            (AnnotationType::Masked, effects(&[Effect::CrossedOut])),
+        ])
+    }
+
+    pub fn attribute(&self, annotation_type: AnnotationType) -> Attribute {
+        self.attributes
+            .get(&annotation_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The attribute of the annotation type called `name` in the configuration file.
+    pub fn attribute_mut(&mut self, name: &str) -> Result<&mut Attribute, String> {
+        let annotation_type = ANNOTATION_NAMES
+            .iter()
+            .find(|(annotation_name, _)| *annotation_name == name)
+            .map(|(_, annotation_type)| *annotation_type)
+            .ok_or_else(|| {
+                let names: Vec<&str> = ANNOTATION_NAMES.iter().map(|(name, _)| *name).collect();
+
+                format!(
+                    "Unknown theme part '{name}', expected one of: {}",
+                    names.join(", ")
+                )
+            })?;
+
+        Ok(self.attributes.entry(annotation_type).or_default())
+    }
+
+    /// Brings the colors down to what the terminal can show.
+    pub fn adapt(self, depth: ColorDepth) -> Self {
+        if depth == ColorDepth::Monochrome {
+            return Self::monochrome();
+        }
+
+        Self {
+            attributes: self
+                .attributes
+                .into_iter()
+                .map(|(annotation_type, attribute)| {
+                    let attribute = Attribute {
+                        foreground: attribute.foreground.map(|color| depth.adapt(color, false)),
+                        background: attribute.background.map(|color| depth.adapt(color, true)),
+                        ..attribute
+                    };
+
+                    (annotation_type, attribute)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A color name, `#rrggbb`, or `default` for the color of the terminal.
+pub fn parse_color(value: &str) -> Result<Option<Color>, String> {
+    if value == "default" {
+        return Ok(None);
+    }
+
+    if let Some(hex) = value.strip_prefix('#')
+        && hex.len() == 6
+        && hex.chars().all(|ch| ch.is_ascii_hexdigit())
+        && let Ok(rgb) = u32::from_str_radix(hex, 16)
+    {
+        let [_, r, g, b] = rgb.to_be_bytes();
+
+        return Ok(Some(Color::Rgb { r, g, b }));
+    }
+
+    COLOR_NAMES
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, color)| Some(*color))
+        .ok_or_else(|| {
+            format!("Unknown color '{value}', expected a color name, #rrggbb or default")
+        })
+}
+
+pub fn parse_effect(value: &str) -> Result<Effect, String> {
+    EFFECT_NAMES
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, effect)| *effect)
+        .ok_or_else(|| {
+            let names: Vec<&str> = EFFECT_NAMES.iter().map(|(name, _)| *name).collect();
+
+            format!(
+                "Unknown effect '{value}', expected one of: {}",
+                names.join(", ")
+            )
+        })
+}
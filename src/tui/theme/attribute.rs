@@ -0,0 +1,59 @@
+use crossterm::style::{Attributes, Color};
+
+/// How a part of a line is drawn.
+#[derive(Clone, Copy, Default)]
+pub struct Attribute {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    /// Bold, underlined, reversed and the like.
+    pub effects: Attributes,
+}
+
+impl Attribute {
+    pub const fn background(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            foreground: None,
+            background: Some(Color::Rgb { r, g, b }),
+            effects: Attributes::none(),
+        }
+    }
+
+    pub const fn foreground(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            foreground: Some(Color::Rgb { r, g, b }),
+            background: None,
+            effects: Attributes::none(),
+        }
+    }
+
+    pub const fn effect(effect: crossterm::style::Attribute) -> Self {
+        Self {
+            foreground: None,
+            background: None,
+            effects: Attributes::none().with(effect),
+        }
+    }
+
+    /// Draws `other` on top: its colors replace these where it has some, effects add up.
+    pub fn overlay(self, other: Self) -> Self {
+        Self {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            effects: self.effects | other.effects,
+        }
+    }
+
+    pub const fn with_foreground(self, foreground: Color) -> Self {
+        Self {
+            foreground: Some(foreground),
+            ..self
+        }
+    }
+
+    pub const fn with_effect(self, effect: crossterm::style::Attribute) -> Self {
+        Self {
+            effects: self.effects.with(effect),
+            ..self
+        }
+    }
+}
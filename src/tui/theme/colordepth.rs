@@ -0,0 +1,327 @@
+use crossterm::style::Color;
+use std::env;
+
+/// The 16 basic colors with the RGB values terminals commonly give them.
+const BASIC_COLORS: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::DarkRed, [128, 0, 0]),
+    (Color::DarkGreen, [0, 128, 0]),
+    (Color::DarkYellow, [128, 128, 0]),
+    (Color::DarkBlue, [0, 0, 128]),
+    (Color::DarkMagenta, [128, 0, 128]),
+    (Color::DarkCyan, [0, 128, 128]),
+    (Color::Grey, [192, 192, 192]),
+    (Color::DarkGrey, [128, 128, 128]),
+    (Color::Red, [255, 0, 0]),
+    (Color::Green, [0, 255, 0]),
+    (Color::Yellow, [255, 255, 0]),
+    (Color::Blue, [0, 0, 255]),
+    (Color::Magenta, [255, 0, 255]),
+    (Color::Cyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Levels of a channel in the 6x6x6 color cube of 256 color terminals.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// `NO_COLOR` is set, or the terminal is dumb.
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// Guesses the depth from `NO_COLOR`, `COLORTERM` and `TERM`, the way most terminal
+    /// programs do.
+    pub fn detect() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+
+        Self::from_vars(
+            env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+            &var("COLORTERM"),
+            &var("TERM"),
+        )
+    }
+
+    fn from_vars(no_color: bool, colorterm: &str, term: &str) -> Self {
+        if no_color {
+            return Self::Monochrome;
+        }
+
+        if matches!(colorterm, "truecolor" | "24bit") || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term == "dumb" {
+            Self::Monochrome
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// The closest color the terminal can show. Backgrounds never become black or white,
+    /// which could not be told apart from the terminal background.
+    pub fn adapt(self, color: Color, is_background: bool) -> Color {
+        match (self, color) {
+            (Self::TrueColor | Self::Monochrome, _) | (Self::Ansi256, Color::AnsiValue(_)) => color,
+            (Self::Ansi256, Color::Rgb { r, g, b }) => Color::AnsiValue(to_ansi_256([r, g, b])),
+            (Self::Ansi16, Color::Rgb { r, g, b }) => closest_basic([r, g, b], is_background),
+            (Self::Ansi16, Color::AnsiValue(value)) => match BASIC_COLORS.get(usize::from(value)) {
+                Some((basic, _)) => *basic,
+                None => closest_basic(ansi_256_to_rgb(value), is_background),
+            },
+            (Self::Ansi256 | Self::Ansi16, basic) => basic,
+        }
+    }
+}
+
+fn distance(from: [u8; 3], to: [u8; 3]) -> u32 {
+    from.iter()
+        .zip(to)
+        .map(|(from, to)| u32::from(from.abs_diff(to)).pow(2))
+        .sum()
+}
+
+/// Picks the basic color of the same hue, or a grey for unsaturated colors, in its dark
+/// variant for dim colors. Nearest by distance would turn most dark tints into black or red.
+fn closest_basic(rgb: [u8; 3], is_background: bool) -> Color {
+    let [r, g, b] = rgb.map(i32::from);
+    let max = r.max(g).max(b);
+    let chroma = max.saturating_sub(r.min(g).min(b));
+
+    if chroma.saturating_mul(4) < max || chroma < 24 {
+        return match max {
+            0..64 if !is_background => Color::Black,
+            0..160 => Color::DarkGrey,
+            160..224 => Color::Grey,
+            _ if is_background => Color::Grey,
+            _ => Color::White,
+        };
+    }
+
+    let sixth = |from: i32, to: i32| {
+        from.saturating_sub(to)
+            .saturating_mul(60)
+            .checked_div(chroma)
+            .unwrap_or_default()
+    };
+    let hue = if max == r {
+        sixth(g, b)
+    } else if max == g {
+        sixth(b, r).saturating_add(120)
+    } else {
+        sixth(r, g).saturating_add(240)
+    };
+    let sector = hue
+        .saturating_add(390)
+        .checked_rem(360)
+        .and_then(|hue| hue.checked_div(60))
+        .unwrap_or_default();
+
+    let (bright, dark) = match sector {
+        0 => (Color::Red, Color::DarkRed),
+        1 => (Color::Yellow, Color::DarkYellow),
+        2 => (Color::Green, Color::DarkGreen),
+        3 => (Color::Cyan, Color::DarkCyan),
+        4 => (Color::Blue, Color::DarkBlue),
+        _ => (Color::Magenta, Color::DarkMagenta),
+    };
+
+    if max >= 192 { bright } else { dark }
+}
+
+/// Index of the closest level in the color cube.
+fn cube_index(channel: u8) -> u8 {
+    CUBE_LEVELS
+        .iter()
+        .zip(0_u8..)
+        .min_by_key(|(level, _)| level.abs_diff(channel))
+        .map_or(0, |(_, idx)| idx)
+}
+
+/// The closest of the 6x6x6 color cube and the 24 greys of 256 color terminals.
+fn to_ansi_256(rgb: [u8; 3]) -> u8 {
+    let [r, g, b] = rgb.map(cube_index);
+    let cube = 16_u8
+        .saturating_add(r.saturating_mul(36))
+        .saturating_add(g.saturating_mul(6))
+        .saturating_add(b);
+
+    let average = rgb.iter().map(|channel| u16::from(*channel)).sum::<u16>();
+    let average = u8::try_from(average.checked_div(3).unwrap_or_default()).unwrap_or(u8::MAX);
+    let grey = 232_u8.saturating_add(
+        average
+            .saturating_sub(8)
+            .checked_div(10)
+            .unwrap_or_default()
+            .min(23),
+    );
+
+    if distance(rgb, ansi_256_to_rgb(grey)) < distance(rgb, ansi_256_to_rgb(cube)) {
+        grey
+    } else {
+        cube
+    }
+}
+
+fn ansi_256_to_rgb(value: u8) -> [u8; 3] {
+    if let Some((_, rgb)) = BASIC_COLORS.get(usize::from(value)) {
+        return *rgb;
+    }
+
+    if value >= 232 {
+        let level = value
+            .saturating_sub(232)
+            .saturating_mul(10)
+            .saturating_add(8);
+
+        return [level; 3];
+    }
+
+    let idx = value.saturating_sub(16);
+    let level = |idx: u8| {
+        CUBE_LEVELS
+            .get(usize::from(idx.checked_rem(6).unwrap_or_default()))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    [
+        level(idx.checked_div(36).unwrap_or_default()),
+        level(idx.checked_div(6).unwrap_or_default()),
+        level(idx),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_depth_from_the_environment() {
+        let cases = [
+            (true, "truecolor", "xterm-256color", ColorDepth::Monochrome),
+            (false, "truecolor", "xterm", ColorDepth::TrueColor),
+            (false, "24bit", "", ColorDepth::TrueColor),
+            (false, "", "xterm-direct", ColorDepth::TrueColor),
+            (false, "yes", "tmux-256color", ColorDepth::Ansi256),
+            (false, "", "dumb", ColorDepth::Monochrome),
+            (false, "", "xterm", ColorDepth::Ansi16),
+            (false, "", "", ColorDepth::Ansi16),
+        ];
+
+        for (no_color, colorterm, term, depth) in cases {
+            assert_eq!(
+                ColorDepth::from_vars(no_color, colorterm, term),
+                depth,
+                "COLORTERM={colorterm} TERM={term}"
+            );
+        }
+    }
+
+    #[test]
+    fn rounds_channels_to_the_nearest_cube_level() {
+        let cases = [
+            (0, 0),
+            (47, 0),
+            (48, 1),
+            (115, 1),
+            (116, 2),
+            (155, 2),
+            (156, 3),
+            (195, 3),
+            (196, 4),
+            (235, 4),
+            (236, 5),
+            (255, 5),
+        ];
+
+        for (channel, idx) in cases {
+            assert_eq!(cube_index(channel), idx, "channel {channel}");
+        }
+    }
+
+    #[test]
+    fn picks_the_closest_of_the_cube_and_the_grey_ramp() {
+        let cases = [
+            ([255, 0, 0], 196),
+            ([95, 135, 175], 67),
+            ([0, 0, 0], 16),
+            ([255, 255, 255], 231),
+            ([8, 8, 8], 232),
+            ([18, 18, 18], 233),
+            ([128, 128, 128], 244),
+            ([238, 238, 238], 255),
+            ([95, 95, 95], 59),
+        ];
+
+        for (rgb, value) in cases {
+            assert_eq!(to_ansi_256(rgb), value, "{rgb:?}");
+        }
+
+        for value in 16..=255 {
+            assert_eq!(to_ansi_256(ansi_256_to_rgb(value)), value);
+        }
+    }
+
+    #[test]
+    fn picks_the_basic_color_of_the_same_hue() {
+        let cases = [
+            ([255, 0, 0], Color::Red),
+            ([128, 0, 0], Color::DarkRed),
+            ([60, 150, 30], Color::DarkGreen),
+            ([250, 190, 60], Color::Yellow),
+            ([35, 55, 95], Color::DarkBlue),
+            ([0, 200, 200], Color::Cyan),
+            ([200, 0, 220], Color::Magenta),
+        ];
+
+        for (rgb, color) in cases {
+            assert_eq!(closest_basic(rgb, false), color, "{rgb:?}");
+        }
+    }
+
+    #[test]
+    fn turns_unsaturated_colors_into_greys() {
+        let cases = [
+            ([63, 63, 63], Color::Black, Color::DarkGrey),
+            ([64, 64, 64], Color::DarkGrey, Color::DarkGrey),
+            ([159, 159, 159], Color::DarkGrey, Color::DarkGrey),
+            ([160, 160, 160], Color::Grey, Color::Grey),
+            ([200, 180, 180], Color::Grey, Color::Grey),
+            ([224, 224, 224], Color::White, Color::Grey),
+        ];
+
+        for (rgb, foreground, background) in cases {
+            assert_eq!(closest_basic(rgb, false), foreground, "{rgb:?}");
+            assert_eq!(closest_basic(rgb, true), background, "{rgb:?}");
+        }
+    }
+
+    #[test]
+    fn adapts_colors_to_the_depth() {
+        let rgb = Color::Rgb { r: 255, g: 0, b: 0 };
+
+        assert_eq!(ColorDepth::TrueColor.adapt(rgb, false), rgb);
+        assert_eq!(ColorDepth::Monochrome.adapt(rgb, false), rgb);
+        assert_eq!(ColorDepth::Ansi256.adapt(rgb, false), Color::AnsiValue(196));
+        assert_eq!(ColorDepth::Ansi16.adapt(rgb, false), Color::Red);
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::AnsiValue(4), false),
+            Color::DarkBlue
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::AnsiValue(231), false),
+            Color::White
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::AnsiValue(231), true),
+            Color::Grey
+        );
+        assert_eq!(ColorDepth::Ansi256.adapt(Color::Green, false), Color::Green);
+    }
+}
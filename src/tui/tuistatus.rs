@@ -1,8 +1,15 @@
-use super::Mode;
+use super::{Mode, QuickFilter, SortOrder};
+use crate::systemd::Listing;
 
 #[derive(Default, Eq, PartialEq)]
 pub struct TuiStatus {
     pub mode: Mode,
+    pub listing: Listing,
+    pub marked: usize,
+    /// Failed units of the whole listing, whatever the filter shows.
+    pub failed: usize,
+    pub quick_filter: Option<QuickFilter>,
+    pub sort: SortOrder,
     pub total_lines: usize,
     pub current_line_idx: usize,
 }
@@ -1,12 +1,20 @@
+mod confirmdialog;
+mod detailpane;
 mod filterbar;
+mod logview;
 mod messagebar;
+mod searchable;
 mod searchbar;
 mod statusbar;
 mod uicomponent;
 mod view;
 
+pub use confirmdialog::ConfirmDialog;
+pub use detailpane::DetailPane;
 pub use filterbar::FilterBar;
+pub use logview::LogView;
 pub use messagebar::MessageBar;
+pub use searchable::Searchable;
 pub use searchbar::SearchBar;
 pub use statusbar::StatusBar;
 pub use uicomponent::UIComponent;
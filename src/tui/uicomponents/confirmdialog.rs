@@ -0,0 +1,79 @@
+use std::io::Error;
+
+use super::{super::Terminal, UIComponent};
+use crate::prelude::*;
+
+/// Asks before operations run, listing the command that is going to run for each unit.
+#[derive(Default)]
+pub struct ConfirmDialog {
+    title: String,
+    commands: Vec<String>,
+    needs_redraw: bool,
+    size: Size,
+}
+
+impl ConfirmDialog {
+    pub fn open(&mut self, title: String, commands: Vec<String>) {
+        self.title = title;
+        self.commands = commands;
+        self.set_needs_redraw(true);
+    }
+}
+
+impl UIComponent for ConfirmDialog {
+    fn set_needs_redraw(&mut self, value: bool) {
+        self.needs_redraw = value;
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+        if self.size.height == 0 {
+            return Ok(());
+        }
+
+        Terminal::print_inverted_row(origin_row, &format!("confirm: {}", self.title))?;
+
+        // The last row tells how many commands did not fit, if any.
+        let available = self.size.height.saturating_sub(2);
+        let hidden = self.commands.len().saturating_sub(available);
+        let shown = if hidden > 0 {
+            available.saturating_sub(1)
+        } else {
+            available
+        };
+
+        let mut lines: Vec<String> = self
+            .commands
+            .iter()
+            .take(shown)
+            .map(|command| format!("  $ {command}"))
+            .collect();
+
+        if hidden > 0 {
+            lines.push(format!(
+                "  ... and {} more",
+                self.commands.len().saturating_sub(shown)
+            ));
+        }
+
+        Terminal::print_row(origin_row.saturating_add(1), "")?;
+
+        for row in 0..available {
+            let line: String = lines
+                .get(row)
+                .map(|line| line.chars().take(self.size.width).collect())
+                .unwrap_or_default();
+
+            Terminal::print_row(origin_row.saturating_add(row).saturating_add(2), &line)?;
+        }
+
+        Ok(())
+    }
+}
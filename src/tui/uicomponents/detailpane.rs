@@ -0,0 +1,169 @@
+use std::io::Error;
+
+use super::super::{
+    Terminal, UIComponent,
+    format::{format_age, format_bytes, format_date},
+};
+use crate::{
+    prelude::*,
+    systemd::{Journal, Properties},
+};
+
+/// Status of the selected unit, `systemctl status` style, followed by its latest journal entries.
+#[derive(Default)]
+pub struct DetailPane {
+    unit: Option<String>,
+    lines: Vec<String>,
+    needs_redraw: bool,
+    size: Size,
+}
+
+/// Timestamps come as `@<seconds>`, see `systemctl show --timestamp=unix`.
+fn parse_timestamp(value: &str) -> Option<u64> {
+    value.strip_prefix('@')?.parse().ok()
+}
+
+impl DetailPane {
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Forgets the shown unit, so the next update is not skipped.
+    pub fn invalidate(&mut self) {
+        self.unit = None;
+    }
+
+    /// Number of journal entries that fit below the status.
+    pub fn journal_lines(&self) -> usize {
+        self.size.height.saturating_sub(8)
+    }
+
+    pub fn clear(&mut self) {
+        self.unit = None;
+        self.lines.clear();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn update(
+        &mut self,
+        unit: String,
+        properties: Result<Properties, Error>,
+        journal: Result<Journal, Error>,
+    ) {
+        self.lines.clear();
+
+        match properties {
+            Ok(properties) => self.push_status(&properties),
+            Err(err) => self.lines.push(format!("Could not read the status: {err}")),
+        }
+
+        self.lines.push(String::new());
+
+        match journal {
+            Ok(journal) => self.lines.extend(journal.entries),
+            Err(err) => self
+                .lines
+                .push(format!("Could not read the journal: {err}")),
+        }
+
+        self.unit = Some(unit);
+        self.set_needs_redraw(true);
+    }
+
+    fn push_status(&mut self, properties: &Properties) {
+        let property = |key: &str| properties.get(key).map_or("", String::as_str);
+        let is_set = |value: &str| !value.is_empty() && value != "[not set]" && value != "0";
+
+        self.lines
+            .push(format!("{} - {}", property("Id"), property("Description")));
+
+        let mut loaded = format!("     Loaded: {}", property("LoadState"));
+        if is_set(property("UnitFileState")) {
+            loaded = format!("{loaded} ({}", property("UnitFileState"));
+            if is_set(property("UnitFilePreset")) {
+                loaded = format!("{loaded}; preset: {}", property("UnitFilePreset"));
+            }
+            loaded.push(')');
+        }
+        self.lines.push(loaded);
+
+        let mut active = format!(
+            "     Active: {} ({})",
+            property("ActiveState"),
+            property("SubState")
+        );
+        if let Some(since) = parse_timestamp(property("ActiveEnterTimestamp")) {
+            active = format!(
+                "{active} since {}; {}",
+                format_date(since),
+                format_age(since)
+            );
+        }
+        self.lines.push(active);
+
+        if is_set(property("MainPID")) {
+            self.lines
+                .push(format!("   Main PID: {}", property("MainPID")));
+        }
+
+        if let Ok(memory) = property("MemoryCurrent").parse::<u64>() {
+            self.lines
+                .push(format!("     Memory: {}", format_bytes(memory)));
+        }
+
+        if is_set(property("TasksCurrent")) {
+            self.lines
+                .push(format!("      Tasks: {}", property("TasksCurrent")));
+        }
+    }
+}
+
+impl UIComponent for DetailPane {
+    fn set_needs_redraw(&mut self, value: bool) {
+        self.needs_redraw = value;
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+        if self.size.height == 0 {
+            return Ok(());
+        }
+
+        let title = self.unit.as_deref().unwrap_or_default();
+        Terminal::print_inverted_row(origin_row, &format!("status: {title}"))?;
+
+        // The status always fits, the journal is cut from the top so the latest entries stay
+        // visible.
+        let available = self.size.height.saturating_sub(1);
+        let status_len = self
+            .lines
+            .iter()
+            .position(String::is_empty)
+            .map_or(self.lines.len(), |idx| idx.saturating_add(1));
+        let journal_skip = self.lines.len().saturating_sub(available.max(status_len));
+
+        let mut lines = self.lines.iter().take(status_len).chain(
+            self.lines
+                .iter()
+                .skip(status_len.saturating_add(journal_skip)),
+        );
+
+        for row in 1..=available {
+            let line: String = lines
+                .next()
+                .map(|line| line.chars().take(self.size.width).collect())
+                .unwrap_or_default();
+
+            Terminal::print_row(origin_row.saturating_add(row), &line)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use std::{cmp::min, io::Error};
 
 use super::super::{
-    Terminal,
+    FilterMode, FilterQuery, Terminal,
     command::Edit::{self, DeleteBackward, Insert},
     uicomponents::UIComponent,
 };
@@ -12,6 +12,10 @@ const PROMT: &str = "$ ";
 #[derive(Default)]
 pub struct FilterBar {
     value: String,
+    mode: FilterMode,
+    /// The last value that parsed, kept while the value has a syntax error.
+    query: FilterQuery,
+    error: Option<String>,
     needs_redraw: bool,
     size: Size,
 }
@@ -30,9 +34,26 @@ impl UIComponent for FilterBar {
     }
 
     fn draw(&mut self, origin: RowIdx) -> Result<(), Error> {
-        let message = format!("{}{}", PROMT, self.value);
+        let message = format!("{}{}{}", self.prompt(), PROMT, self.value);
+        let remainder_len = self.size.width.saturating_sub(message.len());
         let to_print = if message.len() <= self.size.width {
-            message
+            // Why the value is invalid goes on the right, or only that it is when that does not fit.
+            let error = self.error.as_ref().map(|error| {
+                let error = format!("invalid filter: {error}");
+
+                if error.len() < remainder_len {
+                    error
+                } else {
+                    String::from("invalid filter")
+                }
+            });
+
+            match error {
+                Some(error) if error.len() < remainder_len => {
+                    format!("{message}{error:>remainder_len$}")
+                }
+                _ => message,
+            }
         } else {
             String::new()
         };
@@ -42,6 +63,10 @@ impl UIComponent for FilterBar {
 }
 
 impl FilterBar {
+    fn prompt(&self) -> String {
+        format!("{} ", self.mode)
+    }
+
     pub fn handle_edit_command(&mut self, command: Edit) {
         match command {
             Insert(ch) => self.value.push(ch),
@@ -51,21 +76,44 @@ impl FilterBar {
             }
         }
 
+        match self.value.parse() {
+            Ok(query) => {
+                self.query = query;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+
         self.set_needs_redraw(true);
     }
 
     pub fn caret_position_col(&self) -> ColIdx {
-        let max_width = PROMT.len().saturating_add(self.value.len());
+        let max_width = self
+            .prompt()
+            .len()
+            .saturating_add(PROMT.len())
+            .saturating_add(self.value.len());
 
         min(max_width, self.size.width)
     }
 
-    pub fn value(&self) -> String {
-        self.value.clone()
+    pub fn query(&self) -> &FilterQuery {
+        &self.query
+    }
+
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FilterMode) {
+        self.mode = mode;
+        self.set_needs_redraw(true);
     }
 
     pub fn clear_value(&mut self) {
         self.value = String::default();
+        self.query = FilterQuery::default();
+        self.error = None;
         self.set_needs_redraw(true);
     }
 }
@@ -0,0 +1,390 @@
+use std::{cmp::min, io::Error};
+
+use super::{
+    super::{AnnotatedString, Mode, SearchPattern, SortOrder, Terminal, TuiStatus, command::Move},
+    Searchable, UIComponent,
+    view::{Highlighter, LineHighlight, SearchDirection, SearchInfo},
+};
+use crate::{
+    prelude::*,
+    systemd::{Backend, JournalQuery, Listing, Scope},
+};
+
+/// Entries kept at most, older ones are left out.
+const MAX_LINES: usize = 10_000;
+
+/// Journal of a unit, scrollable and searchable like the unit list.
+#[derive(Default)]
+pub struct LogView {
+    scope: Scope,
+    query: JournalQuery,
+    lines: Vec<String>,
+    /// Cursor of the last entry read, following reads only the entries after it.
+    cursor: Option<String>,
+    follow: bool,
+    needs_redraw: bool,
+    size: Size,
+    location: LineIdx,
+    scroll_offset: RowIdx,
+    search_info: Option<SearchInfo>,
+}
+
+impl LogView {
+    /// Reads the journal of `unit`, keeping the priority and time range of the previous one.
+    pub fn open(&mut self, backend: &dyn Backend, scope: Scope, unit: String) -> Result<(), Error> {
+        self.scope = scope;
+        self.query = JournalQuery {
+            unit,
+            lines: Some(MAX_LINES),
+            ..self.query.clone()
+        };
+        self.search_info = None;
+
+        self.reload(backend)?;
+        self.scroll_to_end();
+
+        Ok(())
+    }
+
+    /// Reads the journal anew, after the unit, priority or time range changed.
+    fn reload(&mut self, backend: &dyn Backend) -> Result<(), Error> {
+        let journal = backend.journal(self.scope, &self.query)?;
+
+        self.lines = journal.entries;
+        self.cursor = journal.cursor;
+
+        if self.follow {
+            self.scroll_to_end();
+        } else {
+            self.snap_to_valid_line();
+            self.scroll_location_into_view();
+        }
+
+        self.set_needs_redraw(true);
+
+        Ok(())
+    }
+
+    /// Appends the entries written since the last read and scrolls to the end, the oldest entries
+    /// are dropped beyond `MAX_LINES`.
+    pub fn read_new_entries(&mut self, backend: &dyn Backend) -> Result<(), Error> {
+        let journal = backend.journal(
+            self.scope,
+            &JournalQuery {
+                after_cursor: self.cursor.clone(),
+                ..self.query.clone()
+            },
+        )?;
+
+        if journal.entries.is_empty() {
+            return Ok(());
+        }
+
+        self.lines.extend(journal.entries);
+        self.cursor = journal.cursor.or(self.cursor.take());
+
+        let excess = self.lines.len().saturating_sub(MAX_LINES);
+
+        self.lines.drain(..excess);
+        self.location = self.location.saturating_sub(excess);
+        self.scroll_offset = self.scroll_offset.saturating_sub(excess);
+        self.scroll_to_end();
+        self.set_needs_redraw(true);
+
+        Ok(())
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+
+        if self.follow {
+            self.scroll_to_end();
+        }
+
+        self.set_needs_redraw(true);
+    }
+
+    pub fn next_priority(&mut self, backend: &dyn Backend) -> Result<(), Error> {
+        self.query.priority = self.query.priority.next();
+        self.reload(backend)?;
+        self.set_needs_redraw(true);
+
+        Ok(())
+    }
+
+    pub fn next_time_range(&mut self, backend: &dyn Backend) -> Result<(), Error> {
+        self.query.time_range = self.query.time_range.next();
+        self.reload(backend)?;
+        self.set_needs_redraw(true);
+
+        Ok(())
+    }
+
+    pub fn get_status(&self, mode: Mode, listing: Listing) -> TuiStatus {
+        TuiStatus {
+            mode,
+            listing,
+            marked: 0,
+            failed: 0,
+            quick_filter: None,
+            sort: SortOrder::default(),
+            total_lines: self.lines.len(),
+            current_line_idx: self.location,
+        }
+    }
+
+    /// Rows left for the entries below the title.
+    fn page_height(&self) -> usize {
+        self.size.height.saturating_sub(1)
+    }
+
+    fn scroll(&mut self, to: RowIdx) {
+        let height = self.page_height();
+
+        if to < self.scroll_offset {
+            self.scroll_offset = to;
+        } else if to >= self.scroll_offset.saturating_add(height) {
+            self.scroll_offset = to.saturating_sub(height).saturating_add(1);
+        }
+
+        self.set_needs_redraw(true);
+    }
+
+    fn scroll_location_into_view(&mut self) {
+        self.scroll(self.location);
+    }
+
+    fn center_location(&mut self) {
+        let vertical_mid = self.page_height().div_ceil(2);
+
+        self.scroll_offset = self.location.saturating_sub(vertical_mid);
+        self.set_needs_redraw(true);
+    }
+
+    fn snap_to_valid_line(&mut self) {
+        self.location = min(self.location, self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_to_start(&mut self) {
+        self.location = 0;
+        self.scroll_location_into_view();
+    }
+
+    pub fn scroll_to_end(&mut self) {
+        self.location = self.lines.len().saturating_sub(1);
+        self.scroll_location_into_view();
+    }
+
+    /// Moving up leaves follow mode, as the end would be jumped to again on the next read.
+    pub fn handle_move_command(&mut self, command: Move, multiplier: Option<usize>) {
+        let step = multiplier.unwrap_or(1);
+        let page = self.page_height().saturating_sub(1);
+
+        match command {
+            Move::Up => self.location = self.location.saturating_sub(step),
+            Move::Down => self.location = self.location.saturating_add(step),
+            Move::PageUp => self.location = self.location.saturating_sub(page),
+            Move::PageDown => self.location = self.location.saturating_add(page),
+        }
+
+        if matches!(command, Move::Up | Move::PageUp) {
+            self.follow = false;
+        }
+
+        self.snap_to_valid_line();
+        self.scroll_location_into_view();
+    }
+
+    fn get_search_pattern(&self) -> Option<&SearchPattern> {
+        self.search_info
+            .as_ref()
+            .and_then(|search_info| search_info.pattern.as_ref())
+    }
+
+    fn find(
+        &self,
+        pattern: &SearchPattern,
+        from: LineIdx,
+        direction: SearchDirection,
+    ) -> Option<LineIdx> {
+        let len = self.lines.len();
+        let lines = self.lines.iter().enumerate();
+
+        if direction == SearchDirection::Forward {
+            lines
+                .cycle()
+                .skip(from)
+                .take(len)
+                .find(|(_, line)| pattern.is_match(line))
+                .map(|(idx, _)| idx)
+        } else {
+            lines
+                .rev()
+                .cycle()
+                .skip(len.saturating_sub(from).saturating_sub(1))
+                .take(len)
+                .find(|(_, line)| pattern.is_match(line))
+                .map(|(idx, _)| idx)
+        }
+    }
+
+    fn search_in_direction(&mut self, from: LineIdx, direction: SearchDirection) {
+        if let Some(location) = self
+            .get_search_pattern()
+            .and_then(|pattern| self.find(pattern, from, direction))
+        {
+            self.location = location;
+            self.follow = false;
+            self.center_location();
+        }
+
+        self.set_needs_redraw(true);
+    }
+}
+
+impl Searchable for LogView {
+    fn enter_search(&mut self) {
+        self.search_info = Some(SearchInfo {
+            prev_location: self.location,
+            prev_scroll_offset: self.scroll_offset,
+            pattern: None,
+        });
+    }
+
+    fn search(&mut self, pattern: Option<SearchPattern>) {
+        if let Some(search_info) = &mut self.search_info {
+            search_info.pattern = pattern;
+        }
+
+        self.search_in_direction(self.location, SearchDirection::default());
+    }
+
+    fn search_next(&mut self) {
+        self.search_in_direction(self.location.saturating_add(1), SearchDirection::Forward);
+    }
+
+    fn search_prev(&mut self) {
+        self.search_in_direction(self.location.saturating_sub(1), SearchDirection::Backward);
+    }
+
+    fn exit_search(&mut self) {
+        self.set_needs_redraw(true);
+    }
+
+    fn dismiss_search(&mut self) {
+        if let Some(search_info) = &self.search_info {
+            self.location = search_info.prev_location;
+            self.scroll_offset = search_info.prev_scroll_offset;
+            self.scroll_location_into_view();
+        }
+
+        self.search_info = None;
+        self.set_needs_redraw(true);
+    }
+}
+
+impl UIComponent for LogView {
+    fn set_needs_redraw(&mut self, value: bool) {
+        self.needs_redraw = value;
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size;
+        self.scroll_location_into_view();
+    }
+
+    fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+        let JournalQuery {
+            unit,
+            priority,
+            time_range,
+            ..
+        } = &self.query;
+        let follow = if self.follow { " | following" } else { "" };
+
+        Terminal::print_inverted_row(
+            origin_row,
+            &format!("journal: {unit} | priority: {priority} | range: {time_range}{follow}"),
+        )?;
+
+        if self.lines.is_empty() {
+            Terminal::print_row(origin_row.saturating_add(1), "-- No entries --")?;
+        }
+
+        let mut highlighter = Highlighter::new(self.get_search_pattern(), self.location);
+
+        for row in 0..self.page_height() {
+            let line_idx = row.saturating_add(self.scroll_offset);
+            let current_row = origin_row.saturating_add(row).saturating_add(1);
+
+            if self.lines.is_empty() && row == 0 {
+                continue;
+            }
+
+            let Some(line) = self.lines.get(line_idx) else {
+                Terminal::print_row(current_row, "~")?;
+                continue;
+            };
+
+            // Entries are cut at the terminal width and padded to it, so the selection
+            // highlight spans the whole row.
+            let mut text: String = line.chars().take(self.size.width).collect();
+            text.push_str(&" ".repeat(self.size.width.saturating_sub(text.chars().count())));
+
+            highlighter.highlight(line_idx, &text, true, &LineHighlight::default());
+
+            let mut annotated_string = AnnotatedString::from(&text);
+
+            if let Some(annotations) = highlighter.get_annotations(line_idx) {
+                for annotation in annotations {
+                    annotated_string.add_annotation(
+                        annotation.annotation_type,
+                        annotation.start,
+                        annotation.end,
+                    );
+                }
+            }
+
+            Terminal::print_annotated_row(current_row, &annotated_string)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::{BackendKind, Escalation};
+
+    #[test]
+    fn following_appends_only_new_entries() {
+        let backend = BackendKind::Fake
+            .create(Scope::System, Escalation::default())
+            .unwrap();
+        let mut log_view = LogView::default();
+
+        log_view
+            .open(
+                backend.as_ref(),
+                Scope::System,
+                String::from("docker.service"),
+            )
+            .unwrap();
+        let lines = log_view.lines.clone();
+
+        log_view.toggle_follow();
+        log_view.read_new_entries(backend.as_ref()).unwrap();
+
+        assert_eq!(log_view.lines, lines);
+        assert_eq!(log_view.cursor.as_deref(), Some("2"));
+    }
+}
@@ -7,8 +7,6 @@ use super::super::{Terminal, uicomponents::UIComponent};
 use crate::prelude::*;
 
 const DEFAULT_DURATION: Duration = Duration::new(3, 0);
-const DEFAULT_MESSAGE_LEFT: &str = "alternate loaded/all: f";
-const DEFAULT_MESSAGE_RIGHT: &str = "show keys: o | help: p | exit: ctrl+q";
 
 struct Message {
     text: String,
@@ -25,17 +23,34 @@ impl Default for Message {
 }
 
 impl Message {
-    fn is_expired(&self) -> bool {
-        Instant::now().duration_since(self.time) > DEFAULT_DURATION
+    fn is_expired(&self, timeout: Duration) -> bool {
+        Instant::now().duration_since(self.time) > timeout
     }
 }
 
-#[derive(Default)]
 pub struct MessageBar {
     current_message: Message,
     needs_redraw: bool,
     cleared_after_expiry: bool,
     size: Size,
+    timeout: Duration,
+    /// Shown on the left and right when there is no message.
+    default_left: String,
+    default_right: String,
+}
+
+impl Default for MessageBar {
+    fn default() -> Self {
+        Self {
+            current_message: Message::default(),
+            needs_redraw: false,
+            cleared_after_expiry: false,
+            size: Size::default(),
+            timeout: DEFAULT_DURATION,
+            default_left: String::new(),
+            default_right: String::new(),
+        }
+    }
 }
 
 impl UIComponent for MessageBar {
@@ -44,7 +59,8 @@ impl UIComponent for MessageBar {
     }
 
     fn needs_redraw(&self) -> bool {
-        (!self.cleared_after_expiry && self.current_message.is_expired()) || self.needs_redraw
+        (!self.cleared_after_expiry && self.current_message.is_expired(self.timeout))
+            || self.needs_redraw
     }
 
     fn set_size(&mut self, size: Size) {
@@ -52,13 +68,18 @@ impl UIComponent for MessageBar {
     }
 
     fn draw(&mut self, origin_y: RowIdx) -> Result<(), Error> {
-        if self.current_message.is_expired() {
+        if self.current_message.is_expired(self.timeout) {
             self.cleared_after_expiry = true;
         }
 
-        let message = if self.current_message.is_expired() || self.current_message.text.is_empty() {
-            let remainder_len = self.size.width.saturating_sub(DEFAULT_MESSAGE_LEFT.len());
-            &format!("{DEFAULT_MESSAGE_LEFT}{DEFAULT_MESSAGE_RIGHT:>remainder_len$}")
+        let message = if self.current_message.is_expired(self.timeout)
+            || self.current_message.text.is_empty()
+        {
+            let remainder_len = self.size.width.saturating_sub(self.default_left.len());
+            &format!(
+                "{}{:>remainder_len$}",
+                self.default_left, self.default_right
+            )
         } else {
             &self.current_message.text
         };
@@ -68,6 +89,17 @@ impl UIComponent for MessageBar {
 }
 
 impl MessageBar {
+    /// Sets how long messages are shown before the default message comes back.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn set_default_message(&mut self, left: &str, right: &str) {
+        self.default_left = left.to_string();
+        self.default_right = right.to_string();
+        self.set_needs_redraw(true);
+    }
+
     pub fn update_message(&mut self, new_message: &str) {
         self.current_message = Message {
             text: new_message.to_string(),
@@ -0,0 +1,21 @@
+use super::super::SearchPattern;
+
+/// A component the search bar can search in.
+pub trait Searchable {
+    // Remembers where the search started, so dismissing it can go back there
+    fn enter_search(&mut self);
+
+    // Jumps to the first match of the pattern, starting at the current location.
+    // `None` clears the search
+    fn search(&mut self, pattern: Option<SearchPattern>);
+
+    fn search_next(&mut self);
+
+    fn search_prev(&mut self);
+
+    // Keeps the location of the last match
+    fn exit_search(&mut self);
+
+    // Goes back to where the search started
+    fn dismiss_search(&mut self);
+}
@@ -1,7 +1,7 @@
 use std::{cmp::min, io::Error};
 
 use super::super::{
-    Terminal,
+    SearchOptions, Terminal,
     command::Edit::{self, DeleteBackward, Insert},
     uicomponents::UIComponent,
 };
@@ -12,6 +12,9 @@ const PROMT: &str = "/";
 #[derive(Default)]
 pub struct SearchBar {
     value: String,
+    options: SearchOptions,
+    /// Why the value is not a valid pattern, if it is not.
+    error: Option<String>,
     needs_redraw: bool,
     size: Size,
 }
@@ -31,8 +34,18 @@ impl UIComponent for SearchBar {
 
     fn draw(&mut self, origin: RowIdx) -> Result<(), Error> {
         let message = format!("{}{}", PROMT, self.value);
+        // The options, or why the pattern is invalid, go on the right when they fit.
+        let info = match &self.error {
+            Some(error) => format!("invalid pattern: {error}"),
+            None => format!("{} (alt+r/c/w)", self.options),
+        };
+        let remainder_len = self.size.width.saturating_sub(message.len());
         let to_print = if message.len() <= self.size.width {
-            message
+            if info.len() < remainder_len {
+                format!("{message}{info:>remainder_len$}")
+            } else {
+                message
+            }
         } else {
             String::new()
         };
@@ -68,12 +81,27 @@ impl SearchBar {
         self.value.clone()
     }
 
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.options = options;
+        self.set_needs_redraw(true);
+    }
+
+    pub fn set_error(&mut self, error: Option<String>) {
+        self.error = error;
+        self.set_needs_redraw(true);
+    }
+
     pub fn redraw(&mut self) {
         self.set_needs_redraw(true);
     }
 
     pub fn clear_value(&mut self) {
         self.value = String::default();
+        self.error = None;
         self.set_needs_redraw(true);
     }
 }
@@ -24,7 +24,33 @@ impl UIComponent for StatusBar {
     }
 
     fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
-        let mode_text = self.current_status.mode.to_string();
+        let TuiStatus {
+            mode,
+            listing,
+            marked,
+            failed,
+            quick_filter,
+            sort,
+            ..
+        } = &self.current_status;
+        let mut mode_text = format!("{mode} | {} | {}", listing.scope, listing.unit_type);
+
+        if let Some(quick_filter) = quick_filter {
+            mode_text = format!("{mode_text} | only {quick_filter}");
+        }
+
+        if !sort.is_default() {
+            mode_text = format!("{mode_text} | sort: {sort}");
+        }
+
+        if *failed > 0 {
+            mode_text = format!("{mode_text} | {failed} failed");
+        }
+
+        if *marked > 0 {
+            mode_text = format!("{mode_text} | {marked} marked");
+        }
+
         let position_indicator = self.current_status.position_indicator_to_string();
         let remainder_len = self.size.width.saturating_sub(mode_text.len());
         let status = format!("{mode_text}{position_indicator:>remainder_len$}");
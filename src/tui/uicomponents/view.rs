@@ -1,17 +1,33 @@
-use std::{cmp::min, io::Error};
+use std::{
+    cmp::min,
+    io::Error,
+    time::{Duration, Instant},
+};
 
 mod buffer;
 mod highlighter;
 mod searchdirection;
 mod searchinfo;
 
-use super::super::{Mode, Target, Terminal, TuiStatus, command::Move};
-use super::UIComponent;
-use crate::prelude::*;
+use super::super::{
+    FilterMode, FilterQuery, Mode, QuickFilter, SearchPattern, SortOrder, Terminal, TuiStatus,
+    command::Move,
+};
+use super::{Searchable, UIComponent};
+use crate::{
+    prelude::*,
+    systemd::{Backend, Listing, Target},
+};
 use buffer::Buffer;
-use highlighter::Highlighter;
-use searchdirection::SearchDirection;
-use searchinfo::SearchInfo;
+pub use highlighter::{Highlighter, LineHighlight};
+pub use searchdirection::SearchDirection;
+pub use searchinfo::SearchInfo;
+
+/// How long services whose state changed on a reload stay highlighted.
+const CHANGE_DURATION: Duration = Duration::from_secs(3);
+/// How long reloads take the details of services whose state did not change from the
+/// previous load, instead of asking the backend for them again.
+const DETAILS_MAX_AGE: Duration = Duration::from_secs(30);
 
 #[derive(Default)]
 pub struct View {
@@ -22,6 +38,23 @@ pub struct View {
     scroll_offset: RowIdx,
     search_info: Option<SearchInfo>,
     hilight_selected_line: bool,
+    /// Name of the service a range of marks was started at, so that it stays put when the
+    /// services are reloaded in another order.
+    mark_anchor: Option<String>,
+    changed_at: Option<Instant>,
+    /// Whether the services have the fields `Backend::fill_details` fills in, or do not
+    /// need them because they are unit files.
+    has_details: bool,
+    /// When the details of the loaded services were last all fetched.
+    details_at: Option<Instant>,
+}
+
+/// Whether sorting by `sort` or filtering by `query` and `quick_filter` looks at fields that
+/// only `Backend::fill_details` fills in for loaded units.
+fn needs_details(query: &FilterQuery, quick_filter: Option<QuickFilter>, sort: SortOrder) -> bool {
+    sort.key.needs_details()
+        || query.needs_details()
+        || quick_filter.is_some_and(QuickFilter::needs_details)
 }
 
 impl View {
@@ -30,22 +63,153 @@ impl View {
         self.set_needs_redraw(true);
     }
 
+    pub fn height(&self) -> usize {
+        self.size.height
+    }
+
     pub fn get_selected_service_name(&self) -> Option<String> {
         self.buffer.get_selected_service_name(self.location)
     }
 
-    pub fn load(&mut self, target: Target) -> Result<(), Error> {
-        let buffer = Buffer::load(self.size.width, target)?;
+    /// Loads the services of `listing` in `sort` order, with the fields `quick_filter` needs.
+    /// The filter is cleared when the listing changes, so its query needs none.
+    pub fn load(
+        &mut self,
+        backend: &dyn Backend,
+        listing: Listing,
+        quick_filter: Option<QuickFilter>,
+        sort: SortOrder,
+    ) -> Result<(), Error> {
+        let details = needs_details(&FilterQuery::default(), quick_filter, sort);
+        let buffer = Buffer::load(backend, listing, self.size.width, sort, details, None)?;
 
         self.buffer = buffer;
+        self.has_details = details || listing.target != Target::Memory;
+        self.details_at = (details && listing.target == Target::Memory).then(Instant::now);
+        self.mark_anchor = None;
         self.set_needs_redraw(true);
 
         Ok(())
     }
 
-    pub fn get_status(&self, mode: Mode) -> TuiStatus {
+    /// Loads the services again in `sort` order, keeping the filter, the marks and the
+    /// selected service, and highlighting the services whose state changed for a moment.
+    pub fn reload(
+        &mut self,
+        backend: &dyn Backend,
+        listing: Listing,
+        query: &FilterQuery,
+        mode: FilterMode,
+        quick_filter: Option<QuickFilter>,
+        sort: SortOrder,
+    ) -> Result<(), Error> {
+        let selected = self.get_selected_service_name();
+        let details = needs_details(query, quick_filter, sort);
+        let cached = self
+            .details_at
+            .filter(|details_at| details_at.elapsed() < DETAILS_MAX_AGE);
+        let mut buffer = Buffer::load(
+            backend,
+            listing,
+            self.size.width,
+            sort,
+            details,
+            cached.map(|_| &self.buffer),
+        )?;
+        buffer.keep_marks(&self.buffer);
+        self.has_details = details || listing.target != Target::Memory;
+        self.details_at = (details && listing.target == Target::Memory)
+            .then(|| cached.unwrap_or_else(Instant::now));
+
+        if buffer.find_changes(&self.buffer) {
+            self.changed_at = Some(Instant::now());
+        }
+
+        self.buffer = buffer;
+        self.buffer.filter(query, mode, quick_filter);
+        self.set_needs_redraw(true);
+
+        if let Some(location) = selected.and_then(|name| self.buffer.position(&name)) {
+            self.location = location;
+        }
+
+        self.snap_to_valid_line();
+        self.scroll_location_into_view();
+
+        Ok(())
+    }
+
+    /// Makes the next reload fetch the details of all services, e.g. after operations that
+    /// may have changed them without changing the state of the units.
+    pub fn expire_details(&mut self) {
+        self.details_at = None;
+    }
+
+    /// Time left until the highlight of the changed services fades.
+    pub fn changes_shown_for(&self) -> Option<Duration> {
+        self.changed_at
+            .map(|changed_at| CHANGE_DURATION.saturating_sub(changed_at.elapsed()))
+    }
+
+    pub fn clear_expired_changes(&mut self) {
+        if self.changes_shown_for() == Some(Duration::ZERO) {
+            self.buffer.clear_changes();
+            self.changed_at = None;
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub fn toggle_mark(&mut self) {
+        self.buffer.toggle_mark(self.location);
+        self.set_needs_redraw(true);
+    }
+
+    /// Where the service the range of marks was started at is shown, if it still is.
+    fn mark_anchor_location(&self) -> Option<LineIdx> {
+        self.mark_anchor
+            .as_deref()
+            .and_then(|name| self.buffer.position(name))
+    }
+
+    /// Starts a range of marks at the current location, or marks the range if one was started.
+    /// A range whose first service is no longer shown starts over. Returns whether a range is
+    /// being selected afterwards.
+    pub fn toggle_mark_range(&mut self) -> bool {
+        if let Some(anchor) = self.mark_anchor_location() {
+            self.buffer.mark_range(anchor, self.location);
+            self.mark_anchor = None;
+        } else {
+            self.mark_anchor = self.get_selected_service_name();
+        }
+
+        self.set_needs_redraw(true);
+
+        self.mark_anchor.is_some()
+    }
+
+    pub fn mark_all(&mut self) {
+        self.buffer.mark_all();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.buffer.clear_marks();
+        self.mark_anchor = None;
+        self.set_needs_redraw(true);
+    }
+
+    pub fn get_marked_service_names(&self) -> Vec<String> {
+        self.buffer.get_marked_service_names()
+    }
+
+    pub fn get_status(&self, mode: Mode, listing: Listing) -> TuiStatus {
         TuiStatus {
             mode,
+            listing,
+            marked: self.buffer.marked_count(),
+            failed: self.buffer.failed_count(),
+            quick_filter: None,
+            sort: SortOrder::default(),
             total_lines: self.buffer.height(),
             current_line_idx: self.location,
         }
@@ -102,6 +266,14 @@ impl View {
         self.snap_to_valid_line();
     }
 
+    /// Moves to the service named `name`, or to the first one when it is not shown.
+    pub fn select(&mut self, name: Option<&str>) {
+        self.location = name
+            .and_then(|name| self.buffer.position(name))
+            .unwrap_or_default();
+        self.scroll_location_into_view();
+    }
+
     pub fn scroll_to_start(&mut self) {
         self.move_up(self.location);
         self.scroll(self.location_to_position());
@@ -132,26 +304,37 @@ impl View {
         self.scroll_location_into_view();
     }
 
-    pub fn filter(&mut self, query: &str) {
-        self.buffer.filter(query);
+    /// Whether filtering by `query` and `quick_filter` needs fields the services were loaded
+    /// without, so that they have to be reloaded first.
+    pub fn lacks_details(&self, query: &FilterQuery, quick_filter: Option<QuickFilter>) -> bool {
+        !self.has_details
+            && (query.needs_details() || quick_filter.is_some_and(QuickFilter::needs_details))
+    }
+
+    pub fn filter(
+        &mut self,
+        query: &FilterQuery,
+        mode: FilterMode,
+        quick_filter: Option<QuickFilter>,
+    ) {
+        self.buffer.filter(query, mode, quick_filter);
+        self.mark_anchor = None;
         self.set_needs_redraw(true);
     }
 
-    fn get_search_query(&self) -> Option<&String> {
+    fn get_search_pattern(&self) -> Option<&SearchPattern> {
         self.search_info
             .as_ref()
-            .and_then(|search_info| search_info.query.as_ref())
+            .and_then(|search_info| search_info.pattern.as_ref())
     }
 
     fn search_in_direction(&mut self, from: LineIdx, direction: SearchDirection) {
         // Review possible bug. SearchInfo is not cleared when exiting search mode to keep prev/next search
-        if let Some(location) = self.get_search_query().and_then(|query| {
-            if query.is_empty() {
-                None
-            } else if direction == SearchDirection::Forward {
-                self.buffer.search_forward(query, from)
+        if let Some(location) = self.get_search_pattern().and_then(|pattern| {
+            if direction == SearchDirection::Forward {
+                self.buffer.search_forward(pattern, from)
             } else {
-                self.buffer.search_backward(query, from)
+                self.buffer.search_backward(pattern, from)
             }
         }) {
             self.location = location;
@@ -160,36 +343,38 @@ impl View {
 
         self.set_needs_redraw(true);
     }
+}
 
-    pub fn search_next(&mut self) {
+impl Searchable for View {
+    fn search_next(&mut self) {
         self.search_in_direction(self.location.saturating_add(1), SearchDirection::Forward);
     }
 
-    pub fn search_prev(&mut self) {
+    fn search_prev(&mut self) {
         self.search_in_direction(self.location.saturating_sub(1), SearchDirection::Backward);
     }
 
-    pub fn search(&mut self, query: &str) {
+    fn search(&mut self, pattern: Option<SearchPattern>) {
         if let Some(search_info) = &mut self.search_info {
-            search_info.query = Some(String::from(query));
+            search_info.pattern = pattern;
         }
 
         self.search_in_direction(self.location, SearchDirection::default());
     }
 
-    pub fn enter_search(&mut self) {
+    fn enter_search(&mut self) {
         self.search_info = Some(SearchInfo {
             prev_location: self.location,
             prev_scroll_offset: self.scroll_offset,
-            query: None,
+            pattern: None,
         });
     }
 
-    pub fn exit_search(&mut self) {
+    fn exit_search(&mut self) {
         self.set_needs_redraw(true);
     }
 
-    pub fn dismiss_search(&mut self) {
+    fn dismiss_search(&mut self) {
         if let Some(search_info) = &self.search_info {
             self.location = search_info.prev_location;
             self.scroll_offset = search_info.prev_scroll_offset;
@@ -218,15 +403,25 @@ impl UIComponent for View {
     fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
         let end_y = origin_row.saturating_add(self.size.height);
 
-        let query = self
-            .search_info
-            .as_ref()
-            .and_then(|search_info| search_info.query.as_deref());
-        let mut highlighter = Highlighter::new(query, self.location);
+        // The highlighter keeps the pattern while the buffer is borrowed mutably.
+        let pattern = self.get_search_pattern().cloned();
+        let mut highlighter = Highlighter::new(pattern.as_ref(), self.location);
+        let mark_range = self
+            .mark_anchor_location()
+            .map(|anchor| anchor.min(self.location)..=anchor.max(self.location));
 
         for current_row in 0..end_y.saturating_add(self.scroll_offset) {
-            self.buffer
-                .highlight(current_row, &mut highlighter, self.hilight_selected_line);
+            let marked = self.buffer.is_marked(current_row)
+                || mark_range
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&current_row));
+
+            self.buffer.highlight(
+                current_row,
+                &mut highlighter,
+                self.hilight_selected_line,
+                marked,
+            );
         }
 
         for current_row in origin_row..end_y {
@@ -245,3 +440,211 @@ impl UIComponent for View {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::super::super::sortorder::SortKey;
+    use super::*;
+    use crate::systemd::{
+        BackendKind, Escalation, Journal, JournalQuery, Operation, OperationOutput, OperationType,
+        Properties, Scope, Service, UnitType,
+    };
+
+    /// The fake backend, counting the services whose details are fetched.
+    struct Counting {
+        backend: Box<dyn Backend>,
+        fetched: Cell<usize>,
+    }
+
+    impl Backend for Counting {
+        fn list_units(&self, scope: Scope, unit_type: UnitType) -> Result<Vec<Service>, Error> {
+            self.backend.list_units(scope, unit_type)
+        }
+
+        fn list_unit_files(
+            &self,
+            scope: Scope,
+            unit_type: UnitType,
+        ) -> Result<Vec<Service>, Error> {
+            self.backend.list_unit_files(scope, unit_type)
+        }
+
+        fn may_prompt(&self, operations: &[Operation]) -> bool {
+            self.backend.may_prompt(operations)
+        }
+
+        fn command_line(&self, operation: &Operation) -> String {
+            self.backend.command_line(operation)
+        }
+
+        fn execute(&self, operation: &Operation) -> Result<OperationOutput, Error> {
+            self.backend.execute(operation)
+        }
+
+        fn properties(&self, scope: Scope, name: &str) -> Result<Properties, Error> {
+            self.backend.properties(scope, name)
+        }
+
+        fn fill_details(&self, scope: Scope, services: &mut [Service]) -> Result<(), Error> {
+            self.fetched
+                .set(self.fetched.get().saturating_add(services.len()));
+            self.backend.fill_details(scope, services)
+        }
+
+        fn journal(&self, scope: Scope, query: &JournalQuery) -> Result<Journal, Error> {
+            self.backend.journal(scope, query)
+        }
+    }
+
+    fn fake() -> Box<dyn Backend> {
+        BackendKind::Fake
+            .create(Scope::System, Escalation::None)
+            .unwrap()
+    }
+
+    fn shown(view: &View) -> Vec<String> {
+        (0..view.buffer.height())
+            .filter_map(|idx| view.buffer.get_selected_service_name(idx))
+            .collect()
+    }
+
+    #[test]
+    fn fills_in_unit_file_states_for_the_enabled_inactive_filter() {
+        let backend = fake();
+        let listing = Listing::default();
+        let quick_filter = Some(QuickFilter::EnabledInactive);
+        let mut view = View::default();
+        view.load(backend.as_ref(), listing, None, SortOrder::default())
+            .unwrap();
+
+        assert!(view.lacks_details(&FilterQuery::default(), quick_filter));
+
+        view.reload(
+            backend.as_ref(),
+            listing,
+            &FilterQuery::default(),
+            FilterMode::default(),
+            quick_filter,
+            SortOrder::default(),
+        )
+        .unwrap();
+
+        assert!(!view.lacks_details(&FilterQuery::default(), quick_filter));
+        assert_eq!(shown(&view), ["docker.service"]);
+    }
+
+    #[test]
+    fn fills_in_unit_file_states_and_presets_for_the_query() {
+        let backend = fake();
+        let listing = Listing::default();
+        let query: FilterQuery = "state:failed enabled:yes | preset:enabled state:inactive"
+            .parse()
+            .unwrap();
+        let mut view = View::default();
+        view.load(backend.as_ref(), listing, None, SortOrder::default())
+            .unwrap();
+
+        assert!(view.lacks_details(&query, None));
+
+        view.reload(
+            backend.as_ref(),
+            listing,
+            &query,
+            FilterMode::default(),
+            None,
+            SortOrder::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            shown(&view),
+            ["docker.service", "nginx.service", "ufw.service"]
+        );
+    }
+
+    #[test]
+    fn reuses_the_details_of_services_whose_state_did_not_change() {
+        let backend = Counting {
+            backend: fake(),
+            fetched: Cell::new(0),
+        };
+        let listing = Listing::default();
+        let by_memory = SortOrder {
+            key: SortKey::Memory,
+            descending: false,
+        };
+        let mut view = View::default();
+        let reload = |view: &mut View| {
+            view.reload(
+                &backend,
+                listing,
+                &FilterQuery::default(),
+                FilterMode::default(),
+                None,
+                by_memory,
+            )
+            .unwrap();
+
+            backend.fetched.get()
+        };
+
+        view.load(&backend, listing, None, by_memory).unwrap();
+        let all = backend.fetched.get();
+
+        assert_eq!(all, view.buffer.height());
+        assert_eq!(reload(&mut view), all);
+
+        backend
+            .execute(&Operation {
+                operation_type: OperationType::Stop,
+                name: String::from("cron.service"),
+                scope: Scope::System,
+            })
+            .unwrap();
+
+        assert_eq!(reload(&mut view), all + 1);
+
+        view.expire_details();
+
+        assert_eq!(reload(&mut view), all + 1 + all);
+    }
+
+    #[test]
+    fn keeps_a_range_of_marks_across_a_reload_in_another_order() {
+        let backend = fake();
+        let listing = Listing::default();
+        let mut view = View::default();
+        view.load(backend.as_ref(), listing, None, SortOrder::default())
+            .unwrap();
+
+        view.select(Some("cron.service"));
+        assert!(view.toggle_mark_range());
+        view.select(Some("dbus.service"));
+
+        let by_name_descending = SortOrder {
+            key: SortKey::Name,
+            descending: true,
+        };
+        view.reload(
+            backend.as_ref(),
+            listing,
+            &FilterQuery::default(),
+            FilterMode::default(),
+            None,
+            by_name_descending,
+        )
+        .unwrap();
+
+        assert_eq!(
+            view.get_selected_service_name().as_deref(),
+            Some("dbus.service")
+        );
+        assert!(!view.toggle_mark_range());
+        assert_eq!(
+            view.get_marked_service_names(),
+            ["dbus.service", "cron.service"]
+        );
+    }
+}
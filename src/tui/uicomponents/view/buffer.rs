@@ -1,23 +1,34 @@
-use std::{io::Error, process::Command};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Error,
+};
 
-mod service;
+mod line;
 
-use super::{super::super::AnnotatedString, Highlighter, Target};
-use crate::prelude::*;
-pub use service::Service;
+use super::{
+    super::super::{
+        AnnotatedString, FilterMode, FilterQuery, QuickFilter, SearchPattern, SortOrder,
+    },
+    Highlighter, LineHighlight,
+};
+use crate::{
+    prelude::*,
+    systemd::{Backend, Listing, Service, Target},
+};
+pub use line::Line;
 
 #[derive(Default)]
 pub struct Buffer {
-    services: Vec<Service>,
-    filtered: Option<Vec<Service>>,
+    services: Vec<Line>,
+    filtered: Option<Vec<Line>>,
+    // Marks are kept by unit name, so they survive filtering and reloading.
+    marked: HashSet<String>,
+    // Units whose state differs from the previous load.
+    changed: HashSet<String>,
 }
 
 impl Buffer {
-    fn get_default_collection(&self) -> &Vec<Service> {
-        &self.services
-    }
-
-    fn get_active_collection(&self) -> &Vec<Service> {
+    fn get_active_collection(&self) -> &Vec<Line> {
         if let Some(filtered) = &self.filtered {
             filtered
         } else {
@@ -28,54 +39,234 @@ impl Buffer {
     pub fn get_selected_service_name(&self, idx: LineIdx) -> Option<String> {
         self.get_active_collection()
             .get(idx)
-            .map(Service::extract_name)
+            .map(|line| line.name().to_string())
     }
 
-    pub fn height(&self) -> usize {
-        self.get_active_collection().len()
+    pub fn position(&self, name: &str) -> Option<LineIdx> {
+        self.get_active_collection()
+            .iter()
+            .position(|line| line.name() == name)
     }
 
-    pub fn load(terminal_width: usize, target: Target) -> Result<Self, Error> {
-        let output = if target == Target::Memory {
-            Command::new("systemctl")
-                .args([
-                    "list-units",
-                    "--type=service",
-                    "--all",
-                    "--no-pager",
-                    "--no-legend",
-                    "--plain",
-                ])
-                .output()?
-        } else {
-            Command::new("systemctl")
-                .args([
-                    "list-unit-files",
-                    "--type=service",
-                    "--no-pager",
-                    "--no-legend",
-                    "--plain",
-                ])
-                .output()?
-        };
+    /// Remembers the services whose state differs from the one they had in `other`.
+    /// Returns whether any did.
+    pub fn find_changes(&mut self, other: &Self) -> bool {
+        let previous: HashMap<&str, &Service> = other
+            .services
+            .iter()
+            .map(|line| (line.name(), &line.service))
+            .collect();
+
+        self.changed = self
+            .services
+            .iter()
+            .filter(|line| {
+                previous.get(line.name()).is_some_and(|service| {
+                    service.active_state != line.service.active_state
+                        || service.sub_state != line.service.sub_state
+                        // File states of loaded units are only known when their details are.
+                        || (!service.unit_file_state.is_empty()
+                            && !line.service.unit_file_state.is_empty()
+                            && service.unit_file_state != line.service.unit_file_state)
+                })
+            })
+            .map(|line| line.name().to_string())
+            .collect();
+
+        !self.changed.is_empty()
+    }
+
+    pub fn clear_changes(&mut self) {
+        self.changed.clear();
+    }
+
+    pub fn is_marked(&self, idx: LineIdx) -> bool {
+        self.get_active_collection()
+            .get(idx)
+            .is_some_and(|line| self.marked.contains(line.name()))
+    }
+
+    pub fn toggle_mark(&mut self, idx: LineIdx) {
+        if let Some(name) = self.get_selected_service_name(idx)
+            && !self.marked.remove(&name)
+        {
+            self.marked.insert(name);
+        }
+    }
+
+    /// Marks every line between `from` and `to`, both included and in any order.
+    pub fn mark_range(&mut self, from: LineIdx, to: LineIdx) {
+        let (start, end) = if from <= to { (from, to) } else { (to, from) };
+        let names: Vec<String> = self
+            .get_active_collection()
+            .iter()
+            .skip(start)
+            .take(end.saturating_sub(start).saturating_add(1))
+            .map(|line| line.name().to_string())
+            .collect();
+
+        self.marked.extend(names);
+    }
+
+    /// Marks every line that passes the current filter.
+    pub fn mark_all(&mut self) {
+        let names: Vec<String> = self
+            .get_active_collection()
+            .iter()
+            .map(|line| line.name().to_string())
+            .collect();
+
+        self.marked.extend(names);
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.marked.len()
+    }
+
+    /// Names of the marked services, in listing order.
+    pub fn get_marked_service_names(&self) -> Vec<String> {
+        self.services
+            .iter()
+            .filter(|line| self.marked.contains(line.name()))
+            .map(|line| line.name().to_string())
+            .collect()
+    }
+
+    /// Takes over the marks of `other` for the services that are still listed.
+    pub fn keep_marks(&mut self, other: &Self) {
+        self.marked = self
+            .services
+            .iter()
+            .filter(|line| other.marked.contains(line.name()))
+            .map(|line| line.name().to_string())
+            .collect();
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.services
+            .iter()
+            .filter(|line| QuickFilter::Failed.matches(&line.service))
+            .count()
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut services = Vec::new();
+    pub fn height(&self) -> usize {
+        self.get_active_collection().len()
+    }
 
-        for line in stdout.lines() {
-            let fill = " ".repeat(terminal_width.saturating_sub(line.len()));
-            let mut line = String::from(line);
-            line.push_str(&fill);
+    /// Loads the services of `listing` in `sort` order, with the fields of
+    /// `Backend::fill_details` when `details` is set. They are taken from `cached` for the
+    /// services whose state did not change since it was loaded, and fetched for the others.
+    pub fn load(
+        backend: &dyn Backend,
+        listing: Listing,
+        terminal_width: usize,
+        sort: SortOrder,
+        details: bool,
+        cached: Option<&Self>,
+    ) -> Result<Self, Error> {
+        let mut services = listing.load(backend)?;
 
-            services.push(Service::new(line));
+        if listing.target == Target::Memory && details {
+            Self::fill_details(backend, listing, &mut services, cached)?;
         }
 
+        sort.sort(&mut services);
+
         Ok(Self {
-            services,
+            services: Self::render(services, listing.target, terminal_width, sort),
             filtered: None,
+            marked: HashSet::new(),
+            changed: HashSet::new(),
         })
     }
 
+    fn fill_details(
+        backend: &dyn Backend,
+        listing: Listing,
+        services: &mut [Service],
+        cached: Option<&Self>,
+    ) -> Result<(), Error> {
+        let cached: HashMap<&str, &Service> = cached
+            .iter()
+            .flat_map(|cached| &cached.services)
+            .map(|line| (line.name(), &line.service))
+            .collect();
+        let mut missing = Vec::new();
+
+        for (idx, service) in services.iter_mut().enumerate() {
+            match cached.get(service.name.as_str()) {
+                Some(cached)
+                    if cached.active_state == service.active_state
+                        && cached.sub_state == service.sub_state =>
+                {
+                    service.unit_file_state.clone_from(&cached.unit_file_state);
+                    service.vendor_preset.clone_from(&cached.vendor_preset);
+                    service.memory = cached.memory;
+                    service.active_since = cached.active_since;
+                }
+                _ => missing.push(idx),
+            }
+        }
+
+        let mut fetched: Vec<Service> = missing
+            .iter()
+            .filter_map(|idx| services.get(*idx).cloned())
+            .collect();
+        backend.fill_details(listing.scope, &mut fetched)?;
+
+        for (idx, service) in missing.into_iter().zip(fetched) {
+            if let Some(slot) = services.get_mut(idx) {
+                *slot = service;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lays the services out in aligned columns, each line padded to the terminal width
+    /// so that the selection highlight spans the whole row. The field sorted by comes
+    /// before the description when it is not one of the columns already.
+    fn render(
+        services: Vec<Service>,
+        target: Target,
+        terminal_width: usize,
+        sort: SortOrder,
+    ) -> Vec<Line> {
+        let sorted_by: Vec<Option<String>> = services
+            .iter()
+            .map(|service| sort.key.column(service, target))
+            .collect();
+        let rows: Vec<Vec<&str>> = services
+            .iter()
+            .zip(&sorted_by)
+            .map(|(service, sorted_by)| {
+                let mut columns = service.columns(target);
+
+                if let Some(sorted_by) = sorted_by {
+                    columns.insert(columns.len().saturating_sub(1), sorted_by);
+                }
+
+                columns
+            })
+            .collect();
+        let lines = Listing::align(&rows);
+
+        services
+            .into_iter()
+            .zip(lines)
+            .map(|(service, mut line)| {
+                let fill = " ".repeat(terminal_width.saturating_sub(line.len()));
+                line.push_str(&fill);
+
+                Line::new(service, line)
+            })
+            .collect()
+    }
+
     pub fn get_highlighted_string(
         &self,
         line_idx: LineIdx,
@@ -86,22 +277,36 @@ impl Buffer {
             .map(|service| service.get_annotated_string(highlighter.get_annotations(line_idx)))
     }
 
-    pub fn filter(&mut self, query: &str) {
-        if query.is_empty() {
+    /// Keeps the services that match `query` and `quick_filter`, best matches first in
+    /// fuzzy mode.
+    pub fn filter(
+        &mut self,
+        query: &FilterQuery,
+        mode: FilterMode,
+        quick_filter: Option<QuickFilter>,
+    ) {
+        if query.is_empty() && quick_filter.is_none() {
             self.filtered = None;
             return;
         }
 
-        let mut services = Vec::new();
-        for idx in 0..self.get_default_collection().len() {
-            if let Some(line) = self.services.get(idx)
-                && line.starts_with(query)
-            {
-                services.push(line.clone());
-            }
-        }
+        let lines = self
+            .services
+            .iter()
+            .filter(|line| quick_filter.is_none_or(|filter| filter.matches(&line.service)));
 
-        self.filtered = Some(services);
+        self.filtered = Some(
+            query
+                .rank(lines, mode, |line| &line.service)
+                .into_iter()
+                .map(|(found, line)| {
+                    let mut line = line.clone();
+                    line.filter_matches = found.positions;
+
+                    line
+                })
+                .collect(),
+        );
     }
 
     pub fn highlight(
@@ -109,18 +314,25 @@ impl Buffer {
         idx: LineIdx,
         highlighter: &mut Highlighter,
         highligh_selected_line: bool,
+        marked: bool,
     ) {
         if let Some(line) = self.get_active_collection().get(idx) {
-            highlighter.highlight(idx, line, highligh_selected_line);
+            highlighter.highlight(
+                idx,
+                line.as_str(),
+                highligh_selected_line,
+                &LineHighlight {
+                    marked,
+                    changed: self.changed.contains(line.name()),
+                    state: Highlighter::unit_state(&line.service),
+                    filter_matches: &line.filter_matches,
+                },
+            );
         }
     }
 
-    pub fn search_forward(&self, query: &str, from: LineIdx) -> Option<LineIdx> {
-        if query.is_empty() {
-            return None;
-        }
-
-        for (line_idx, service) in self
+    pub fn search_forward(&self, pattern: &SearchPattern, from: LineIdx) -> Option<LineIdx> {
+        for (line_idx, line) in self
             .get_active_collection()
             .iter()
             .enumerate()
@@ -128,7 +340,7 @@ impl Buffer {
             .skip(from)
             .take(self.get_active_collection().len())
         {
-            if service.contains(query) {
+            if pattern.is_match(line.as_str()) {
                 return Some(line_idx);
             }
         }
@@ -136,12 +348,8 @@ impl Buffer {
         None
     }
 
-    pub fn search_backward(&self, query: &str, from: LineIdx) -> Option<LineIdx> {
-        if query.is_empty() {
-            return None;
-        }
-
-        for (line_idx, service) in self
+    pub fn search_backward(&self, pattern: &SearchPattern, from: LineIdx) -> Option<LineIdx> {
+        for (line_idx, line) in self
             .get_active_collection()
             .iter()
             .enumerate()
@@ -155,7 +363,7 @@ impl Buffer {
             )
             .take(self.get_active_collection().len())
         {
-            if service.contains(query) {
+            if pattern.is_match(line.as_str()) {
                 return Some(line_idx);
             }
         }
@@ -163,3 +371,53 @@ impl Buffer {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::super::sortorder::SortKey;
+    use super::*;
+    use crate::systemd::{BackendKind, Escalation, Scope};
+
+    fn load(sort: SortOrder) -> Buffer {
+        let backend = BackendKind::Fake
+            .create(Scope::System, Escalation::None)
+            .unwrap();
+
+        Buffer::load(backend.as_ref(), Listing::default(), 0, sort, true, None).unwrap()
+    }
+
+    #[test]
+    fn shows_the_field_sorted_by_before_the_description() {
+        let buffer = load(SortOrder {
+            key: SortKey::Memory,
+            descending: true,
+        });
+        let line = buffer.services.first().unwrap();
+
+        assert_eq!(line.name(), "systemd-journald.service");
+        assert!(line.as_str().ends_with("  running  24.0M  Journal Service"));
+    }
+
+    #[test]
+    fn adds_no_column_for_fields_already_shown() {
+        let by_name = load(SortOrder {
+            key: SortKey::Name,
+            descending: false,
+        });
+        let by_memory = load(SortOrder {
+            key: SortKey::Memory,
+            descending: false,
+        });
+        let cron = |buffer: &Buffer| {
+            buffer
+                .services
+                .iter()
+                .find(|line| line.name() == "cron.service")
+                .map(|line| line.as_str().to_string())
+                .unwrap_or_default()
+        };
+
+        assert!(!cron(&by_name).contains("12.0M"));
+        assert!(cron(&by_memory).contains("  running  12.0M  Regular"));
+    }
+}
@@ -0,0 +1,41 @@
+use super::{super::super::super::Annotation, AnnotatedString};
+use crate::{prelude::*, systemd::Service};
+
+/// A service together with the text it is displayed as.
+#[derive(Clone)]
+pub struct Line {
+    pub service: Service,
+    string: String,
+    /// Byte indices of the characters of the name the filter matched.
+    pub filter_matches: Vec<ByteIdx>,
+}
+
+impl Line {
+    pub fn new(service: Service, string: String) -> Self {
+        Self {
+            service,
+            string,
+            filter_matches: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.service.name
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    pub fn get_annotated_string(&self, annotations: Option<&Vec<Annotation>>) -> AnnotatedString {
+        let mut result = AnnotatedString::from(&self.string);
+
+        if let Some(annotations) = annotations {
+            for annotation in annotations {
+                result.add_annotation(annotation.annotation_type, annotation.start, annotation.end);
+            }
+        }
+
+        result
+    }
+}
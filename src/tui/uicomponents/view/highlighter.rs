@@ -1,86 +1,112 @@
 use std::collections::HashMap;
 
-use super::{
-    super::super::{Annotation, AnnotationType},
-    buffer::Service,
-};
-use crate::prelude::*;
+use super::super::super::{Annotation, AnnotationType, SearchPattern};
+use crate::{prelude::*, systemd::Service};
+
+/// What a line is annotated for, besides the selection and the search.
+#[derive(Default)]
+pub struct LineHighlight<'a> {
+    pub marked: bool,
+    pub changed: bool,
+    pub state: Option<AnnotationType>,
+    /// Byte indices of the characters the filter matched.
+    pub filter_matches: &'a [ByteIdx],
+}
 
 #[derive(Default)]
 pub struct Highlighter<'a> {
-    matched_word: Option<&'a str>,
+    pattern: Option<&'a SearchPattern>,
     location: LineIdx,
     highlights: HashMap<LineIdx, Vec<Annotation>>,
 }
 
 impl<'a> Highlighter<'a> {
-    pub fn new(matched_word: Option<&'a str>, location: LineIdx) -> Self {
+    pub fn new(pattern: Option<&'a SearchPattern>, location: LineIdx) -> Self {
         Self {
-            matched_word,
+            pattern,
             location,
             highlights: HashMap::new(),
         }
     }
 
-    pub fn highlight(&mut self, idx: LineIdx, service: &Service, highligh_selected_line: bool) {
-        let mut result = Vec::new();
+    /// The annotation for the state of `service`, from its load, active and file state.
+    pub fn unit_state(service: &Service) -> Option<AnnotationType> {
+        if service.load_state == "masked" || service.unit_file_state.starts_with("masked") {
+            return Some(AnnotationType::Masked);
+        }
 
-        let annotation_type = if self.location == idx && highligh_selected_line {
-            AnnotationType::SelectedMatch
-        } else {
-            AnnotationType::Match
+        match service.active_state.as_str() {
+            "failed" => Some(AnnotationType::Failed),
+            "active" => Some(AnnotationType::Active),
+            "activating" | "deactivating" | "reloading" | "refreshing" => {
+                Some(AnnotationType::Activating)
+            }
+            "inactive" => Some(AnnotationType::Inactive),
+            _ => None,
+        }
+    }
+
+    /// Annotates a line, the annotations added last are drawn on top: the unit state,
+    /// then the mark, change or selection, then the filter and the search matches.
+    pub fn highlight(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        highligh_selected_line: bool,
+        line_highlight: &LineHighlight,
+    ) {
+        let LineHighlight {
+            marked,
+            changed,
+            state,
+            filter_matches,
+        } = *line_highlight;
+        let mut result = Vec::new();
+        let is_selected = self.location == idx && highligh_selected_line;
+        let whole_line = |annotation_type| Annotation {
+            annotation_type,
+            start: 0,
+            end: line.len(),
         };
 
-        if let Some(matched_word) = self.matched_word
-            && !matched_word.is_empty()
-        {
-            service
-                .find_all(matched_word, 0..service.len())
-                .iter()
-                .for_each(|start| {
-                    result.push(Annotation {
-                        annotation_type,
-                        start: *start,
-                        end: start.saturating_add(matched_word.len()),
-                    });
-                });
+        if let Some(state) = state {
+            result.push(whole_line(state));
         }
 
-        let result = if self.location == idx && highligh_selected_line {
-            let mut char_idx = 0;
-            let mut annotation_idx = 0;
-            let mut selected_annotations = result.clone();
+        if is_selected && marked {
+            result.push(whole_line(AnnotationType::SelectedMarked));
+        } else if is_selected {
+            result.push(whole_line(AnnotationType::Selected));
+        } else if marked {
+            result.push(whole_line(AnnotationType::Marked));
+        } else if changed {
+            result.push(whole_line(AnnotationType::Changed));
+        }
 
-            while char_idx < service.len() {
-                if let Some(annotation) = result.get(annotation_idx) {
-                    if char_idx < annotation.start {
-                        selected_annotations.insert(
-                            annotation_idx,
-                            Annotation {
-                                annotation_type: AnnotationType::Selected,
-                                start: char_idx,
-                                end: annotation.start.saturating_sub(1),
-                            },
-                        );
-                    }
+        result.extend(filter_matches.iter().filter_map(|start| {
+            let ch = line.get(*start..)?.chars().next()?;
 
-                    char_idx = annotation.end.saturating_add(1);
-                    annotation_idx = annotation_idx.saturating_add(1);
-                } else {
-                    selected_annotations.push(Annotation {
-                        annotation_type: AnnotationType::Selected,
-                        start: char_idx,
-                        end: service.len(),
-                    });
-                    break;
-                }
-            }
+            Some(Annotation {
+                annotation_type: AnnotationType::FilterMatch,
+                start: *start,
+                end: start.saturating_add(ch.len_utf8()),
+            })
+        }));
 
-            selected_annotations
+        let annotation_type = if is_selected {
+            AnnotationType::SelectedMatch
         } else {
-            result
+            AnnotationType::Match
         };
 
+        if let Some(pattern) = self.pattern {
+            result.extend(pattern.find_iter(line).map(|range| Annotation {
+                annotation_type,
+                start: range.start,
+                end: range.end,
+            }));
+        }
+
         self.highlights.insert(idx, result);
     }
 